@@ -0,0 +1,80 @@
+use std::env;
+
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::{site_access_middleware::SiteAccessMiddleware, site_middleware::SiteMiddleware, user_middleware::UserMiddleware},
+};
+
+#[derive(Clone, Default)]
+/// Grants a user access to a site.
+///
+/// Args:
+/// - `login`: the login of the user (asked if missing),
+/// - `site`: the subdomain of the site (asked if missing),
+/// - `expires`: expiration date of the access (`YYYY-MM-DD`).
+pub struct AccessGrantCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for AccessGrantCommand {
+    fn name(&self) -> &'a str {
+        "access:grant"
+    }
+
+    fn description(&self) -> &'a str {
+        "Grants a user access to a site."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("login", ArgumentKind::STRING, "The login of the user.").positional(),
+            CommandArgument::new("site", ArgumentKind::STRING, "The subdomain of the site.").positional(),
+            CommandArgument::new("expires", ArgumentKind::DATE, "Expiration date of the access."),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+        let site_middleware = SiteMiddleware::new(db_conn.get_new_connection());
+        let site_access_middleware = SiteAccessMiddleware::new(db_conn.get_new_connection());
+
+        let login = match args.get_string("login") {
+            Some(login) => login,
+            None => io.ask_question("login", "Login:")?,
+        };
+
+        let subdomain = match args.get_string("site") {
+            Some(subdomain) => subdomain,
+            None => io.ask_question("site", "Site (subdomain):")?,
+        };
+
+        let expires_at = args.get_date("expires");
+
+        let user = user_middleware.get_by_login(&login).await?;
+        let site = site_middleware.get_by_subdomain(&subdomain).await?;
+
+        // the grantor is the system user running the console.
+        let granted_by = format!("console:{}", env::var("USER").unwrap_or("unknown".to_string()));
+
+        let site_access = site_access_middleware.grant(&user, &site, &granted_by, expires_at).await?;
+
+        io.success(&format!(
+            "User {} can access site {} (expires at: {}).",
+            user.login,
+            site.subdomain,
+            site_access.expires_at.map(|date| date.to_rfc3339()).unwrap_or("never".to_string()),
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::{site_access_middleware::SiteAccessMiddleware, site_middleware::SiteMiddleware, user_middleware::UserMiddleware},
+};
+
+#[derive(Clone, Default)]
+/// Lists the site accesses.
+///
+/// Args:
+/// - `login`: only list the accesses of this user,
+/// - `site`: only list the accesses to this site (subdomain).
+pub struct AccessListCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for AccessListCommand {
+    fn name(&self) -> &'a str {
+        "access:list"
+    }
+
+    fn description(&self) -> &'a str {
+        "Lists the site accesses, optionally filtered by user or site."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::parallel()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("login", ArgumentKind::STRING, "Only lists the accesses of this user."),
+            CommandArgument::new("site", ArgumentKind::STRING, "Only lists the accesses to this site (subdomain)."),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+        let site_middleware = SiteMiddleware::new(db_conn.get_new_connection());
+        let site_access_middleware = SiteAccessMiddleware::new(db_conn.get_new_connection());
+
+        // resolve the filters.
+        let user = match args.get_string("login") {
+            Some(login) => Some(user_middleware.get_by_login(&login).await?),
+            None => None,
+        };
+
+        let site = match args.get_string("site") {
+            Some(subdomain) => Some(site_middleware.get_by_subdomain(&subdomain).await?),
+            None => None,
+        };
+
+        let site_accesses = site_access_middleware
+            .find_all(
+                user.as_ref().and_then(|user| user.id.as_ref()),
+                site.as_ref().and_then(|site| site.id.as_ref()),
+            )
+            .await?;
+
+        // index users and sites to display their login and subdomain.
+        let logins = user_middleware.find_all().await?
+            .into_iter()
+            .map(|user| (user.id.unwrap(), user.login))
+            .collect::<HashMap<_, _>>();
+
+        let subdomains = site_middleware.find_all().await?
+            .into_iter()
+            .map(|site| (site.id.unwrap(), site.subdomain))
+            .collect::<HashMap<_, _>>();
+
+        let rows = site_accesses
+            .iter()
+            .map(|site_access| vec![
+                logins.get(&site_access.user).cloned().unwrap_or(site_access.user.to_string()),
+                subdomains.get(&site_access.site).cloned().unwrap_or(site_access.site.to_string()),
+                site_access.granted_by.clone(),
+                site_access.granted_at.to_rfc3339(),
+                site_access.expires_at.map(|date| date.to_rfc3339()).unwrap_or("never".to_string()),
+                if site_access.is_expired() { "expired" } else { "active" }.to_string(),
+            ])
+            .collect::<Vec<Vec<String>>>();
+
+        io.table(
+            vec!["User", "Site", "Granted by", "Granted at", "Expires at", "Status"],
+            rows.iter().map(|row| row.iter().map(|cell| cell.as_str()).collect()).collect(),
+        );
+
+        io.info(&format!("{} access(es).", site_accesses.len()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::{site_access_middleware::SiteAccessMiddleware, site_middleware::SiteMiddleware, user_middleware::UserMiddleware},
+};
+
+#[derive(Clone, Default)]
+/// Revokes the access of a user to a site.
+///
+/// Args (asked if missing):
+/// - `login`: the login of the user,
+/// - `site`: the subdomain of the site.
+pub struct AccessRevokeCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for AccessRevokeCommand {
+    fn name(&self) -> &'a str {
+        "access:revoke"
+    }
+
+    fn description(&self) -> &'a str {
+        "Revokes the access of a user to a site."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("login", ArgumentKind::STRING, "The login of the user.").positional(),
+            CommandArgument::new("site", ArgumentKind::STRING, "The subdomain of the site.").positional(),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+        let site_middleware = SiteMiddleware::new(db_conn.get_new_connection());
+        let site_access_middleware = SiteAccessMiddleware::new(db_conn.get_new_connection());
+
+        let login = match args.get_string("login") {
+            Some(login) => login,
+            None => io.ask_question("login", "Login:")?,
+        };
+
+        let subdomain = match args.get_string("site") {
+            Some(subdomain) => subdomain,
+            None => io.ask_question("site", "Site (subdomain):")?,
+        };
+
+        let user = user_middleware.get_by_login(&login).await?;
+        let site = site_middleware.get_by_subdomain(&subdomain).await?;
+
+        site_access_middleware.revoke(&user, &site).await?;
+
+        io.success(&format!("Access of user {} to site {} revoked.", user.login, site.subdomain));
+
+        Ok(())
+    }
+}
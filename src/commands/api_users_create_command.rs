@@ -0,0 +1,72 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::api_user_middleware::ApiUserMiddleware,
+};
+
+#[derive(Clone, Default)]
+/// Creates an API user and prints its API key.
+///
+/// Args:
+/// - `name`: the name of the API user (asked if missing),
+/// - `scopes`: comma-separated scopes granted to the API user,
+/// - `expires`: expiration date of the API key (`YYYY-MM-DD`).
+pub struct ApiUsersCreateCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for ApiUsersCreateCommand {
+    fn name(&self) -> &'a str {
+        "api-users:create"
+    }
+
+    fn description(&self) -> &'a str {
+        "Creates an API user and prints its API key."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("name", ArgumentKind::STRING, "The name of the API user.").positional(),
+            CommandArgument::new("scopes", ArgumentKind::STRING, "Comma-separated scopes granted to the API user."),
+            CommandArgument::new("expires", ArgumentKind::DATE, "Expiration date of the API key."),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let api_user_middleware = ApiUserMiddleware::new(db_conn.get_new_connection());
+
+        let name = match args.get_string("name") {
+            Some(name) => name,
+            None => io.ask_question("name", "Name of the API user:")?,
+        };
+
+        let scopes = args.get_string("scopes")
+            .map(|scopes| scopes.split(',').map(|scope| scope.trim().to_string()).filter(|scope| !scope.is_empty()).collect())
+            .unwrap_or_default();
+
+        let expires_at = args.get_date("expires");
+
+        let (api_user, api_key) = api_user_middleware.create(&name, scopes, expires_at).await?;
+
+        io.key_value_pair(vec![
+            ("Name", api_user.name.clone()),
+            ("Scopes", api_user.scopes.join(", ")),
+            ("Expires at", api_user.expires_at.map(|date| date.to_rfc3339()).unwrap_or("never".to_string())),
+            ("API key", api_key),
+        ]);
+
+        io.warning("Store the API key now, it will not be displayed again.");
+        io.success(&format!("API user {} created.", api_user.name));
+
+        Ok(())
+    }
+}
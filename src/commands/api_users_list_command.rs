@@ -0,0 +1,57 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::CommandArgs, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::api_user_middleware::{ApiUserMiddleware, API_KEY_TAG},
+};
+
+#[derive(Clone, Default)]
+/// Lists the API users.
+pub struct ApiUsersListCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for ApiUsersListCommand {
+    fn name(&self) -> &'a str {
+        "api-users:list"
+    }
+
+    fn description(&self) -> &'a str {
+        "Lists the API users."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::parallel()
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, _args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let api_user_middleware = ApiUserMiddleware::new(db_conn.get_new_connection());
+
+        let api_users = api_user_middleware.find_all().await?;
+
+        let rows = api_users
+            .iter()
+            .map(|api_user| vec![
+                api_user.name.clone(),
+                format!("{}_{}_…", API_KEY_TAG, api_user.key_prefix),
+                api_user.scopes.join(", "),
+                if api_user.enabled { "yes" } else { "no" }.to_string(),
+                api_user.last_used_at.map(|date| date.to_rfc3339()).unwrap_or("never".to_string()),
+                api_user.expires_at.map(|date| date.to_rfc3339()).unwrap_or("never".to_string()),
+            ])
+            .collect::<Vec<Vec<String>>>();
+
+        io.table(
+            vec!["Name", "Key", "Scopes", "Enabled", "Last used at", "Expires at"],
+            rows.iter().map(|row| row.iter().map(|cell| cell.as_str()).collect()).collect(),
+        );
+
+        io.info(&format!("{} API user(s).", api_users.len()));
+
+        Ok(())
+    }
+}
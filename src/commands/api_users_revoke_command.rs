@@ -0,0 +1,54 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::api_user_middleware::ApiUserMiddleware,
+};
+
+#[derive(Clone, Default)]
+/// Revokes the API key of an API user.
+///
+/// Args:
+/// - `name`: the name of the API user (asked if missing).
+pub struct ApiUsersRevokeCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for ApiUsersRevokeCommand {
+    fn name(&self) -> &'a str {
+        "api-users:revoke"
+    }
+
+    fn description(&self) -> &'a str {
+        "Revokes the API key of an API user."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("name", ArgumentKind::STRING, "The name of the API user.").positional(),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let api_user_middleware = ApiUserMiddleware::new(db_conn.get_new_connection());
+
+        let name = match args.get_string("name") {
+            Some(name) => name,
+            None => io.ask_question("name", "Name of the API user:")?,
+        };
+
+        let api_user = api_user_middleware.revoke(&name).await?;
+
+        io.success(&format!("API user {} revoked.", api_user.name));
+
+        Ok(())
+    }
+}
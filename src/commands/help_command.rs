@@ -0,0 +1,117 @@
+use anyhow::{Result, bail};
+use rocket::{Build, Rocket};
+
+use crate::core::commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_registry::CommandRegistry, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::{CommandError, CommandTrait}, command_utils::ConsoleIO};
+
+#[derive(Clone, Default)]
+/// Displays the description and the arguments of a command.
+///
+/// Args:
+/// - `command`: the name of the command (asked if missing).
+pub struct HelpCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for HelpCommand {
+    fn name(&self) -> &'a str {
+        "help"
+    }
+
+    fn description(&self) -> &'a str {
+        "Displays the description and the arguments of a command."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::parallel()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("command", ArgumentKind::STRING, "The name of the command.").positional(),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let command_registry = rocket.state::<CommandRegistry>().unwrap();
+
+        let name = match args.get_string("command") {
+            Some(name) => name,
+            None => io.ask_question("command", "Command:")?,
+        };
+
+        let possible_command = command_registry.get(&name);
+
+        // an unknown command is an invalid argument, the help itself ran.
+        if possible_command.is_none() {
+            let suggestions = command_registry.suggest(&name);
+
+            let message = match suggestions.is_empty() {
+                true => format!("unknown command `{}`, use the `list` command to see all the commands.", name),
+                false => format!("unknown command `{}`, did you mean {}?", name, suggestions.join(" or ")),
+            };
+
+            bail!(CommandError::InvalidArguments(self.name().to_string(), message));
+        }
+
+        let command = possible_command.unwrap();
+        let arguments = command.arguments();
+
+        io.key_value_pair(vec![
+            ("Command", command.name().to_string()),
+            ("Description", command.description().to_string()),
+            ("Usage", usage(command.name(), &arguments)),
+            ("Concurrency", command.concurrency().to_string()),
+            ("Retry", command.retry().to_string()),
+        ]);
+
+        if arguments.is_empty() {
+            io.info("This command has no arguments.");
+            return Ok(());
+        }
+
+        let rows = arguments
+            .iter()
+            .map(|argument| vec![
+                argument.name.to_string(),
+                argument.kind.to_string(),
+                if argument.required { "yes" } else { "no" }.to_string(),
+                argument.default.unwrap_or("").to_string(),
+                argument.help.to_string(),
+            ])
+            .collect::<Vec<Vec<String>>>();
+
+        io.section("Arguments");
+        io.table(
+            vec!["Argument", "Type", "Required", "Default", "Description"],
+            rows.iter().map(|row| row.iter().map(|cell| cell.as_str()).collect()).collect(),
+        );
+
+        Ok(())
+    }
+}
+
+/// Builds the usage line of a command, e.g. `hermes console users:delete [<login>] [--force]`.
+fn usage(name: &str, arguments: &[CommandArgument]) -> String {
+    // positional arguments come first, in declaration order.
+    let ordered = arguments
+        .iter()
+        .filter(|argument| argument.positional)
+        .chain(arguments.iter().filter(|argument| !argument.positional));
+
+    let mut usage = vec![format!("hermes console {}", name)];
+
+    for argument in ordered {
+        let argument_usage = match (argument.positional, &argument.kind) {
+            (true, _) => format!("<{}>", argument.name),
+            (false, ArgumentKind::BOOL) => format!("--{}", argument.name),
+            (false, kind) => format!("--{} <{}>", argument.name, kind),
+        };
+
+        if argument.required {
+            usage.push(argument_usage);
+        } else {
+            usage.push(format!("[{}]", argument_usage));
+        }
+    }
+
+    usage.join(" ")
+}
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::core::commands::{command_args::CommandArgs, command_registry::CommandRegistry, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO};
+
+/// The namespace of the commands without prefix.
+const DEFAULT_NAMESPACE: &str = "general";
+
+#[derive(Clone, Default)]
+/// Lists the registered commands, grouped by namespace (e.g. the `users` of `users:create`).
+pub struct ListCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for ListCommand {
+    fn name(&self) -> &'a str {
+        "list"
+    }
+
+    fn description(&self) -> &'a str {
+        "Lists the available commands."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::parallel()
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, _args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let command_registry = rocket.state::<CommandRegistry>().unwrap();
+
+        // group the commands by namespace.
+        let mut namespaces = BTreeMap::<&str, Vec<Vec<String>>>::new();
+
+        for command in command_registry.all() {
+            let namespace = command.name().split_once(':').map(|(namespace, _)| namespace).unwrap_or(DEFAULT_NAMESPACE);
+
+            namespaces.entry(namespace).or_default().push(vec![
+                command.name().to_string(),
+                command.description().to_string(),
+                command.concurrency().to_string(),
+            ]);
+        }
+
+        for (namespace, rows) in namespaces.iter() {
+            io.section(namespace);
+            io.table(
+                vec!["Command", "Description", "Concurrency"],
+                rows.iter().map(|row| row.iter().map(|cell| cell.as_str()).collect()).collect(),
+            );
+        }
+
+        io.new_line();
+        io.info("Use `help <command>` to see the arguments of a command.");
+
+        Ok(())
+    }
+}
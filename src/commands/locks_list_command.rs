@@ -0,0 +1,59 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::CommandArgs, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::command_middleware::CommandMiddleware,
+};
+
+#[derive(Clone, Default)]
+/// Lists the command locks, with their owner and lease.
+pub struct LocksListCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for LocksListCommand {
+    fn name(&self) -> &'a str {
+        "locks:list"
+    }
+
+    fn description(&self) -> &'a str {
+        "Lists the command locks, with the process owning them and their lease."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::parallel()
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, _args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let command_middleware = CommandMiddleware::new(db_conn.get_new_connection());
+
+        let locks = command_middleware.find_locks().await?;
+
+        let rows = locks
+            .iter()
+            .map(|lock| vec![
+                lock.log.id.to_raw(),
+                lock.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+                lock.command_name.clone(),
+                lock.command_args.clone(),
+                lock.owner.to_string(),
+                lock.acquired_at.to_rfc3339(),
+                lock.lease_expires_at.to_rfc3339(),
+                if lock.is_expired() { "expired" } else { "active" }.to_string(),
+            ])
+            .collect::<Vec<Vec<String>>>();
+
+        io.table(
+            vec!["Log", "Lock", "Command", "Args", "Owner", "Acquired at", "Lease expires at", "Lease"],
+            rows.iter().map(|row| row.iter().map(|cell| cell.as_str()).collect()).collect(),
+        );
+
+        io.info(&format!("{} lock(s), expired leases are taken over by the next run.", locks.len()));
+
+        Ok(())
+    }
+}
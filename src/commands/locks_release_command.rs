@@ -0,0 +1,82 @@
+use anyhow::{Result, bail};
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+        lease,
+    },
+    middlewares::command_middleware::CommandMiddleware,
+    model::command_log::CommandStatus,
+};
+
+#[derive(Clone, Default)]
+/// Releases the locks of a running command, marking it as abandoned.
+///
+/// Args:
+/// - `id`: the id of the command log holding the lock, as displayed by `locks:list` (asked if missing),
+/// - `force`: flag to skip the confirmation.
+pub struct LocksReleaseCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for LocksReleaseCommand {
+    fn name(&self) -> &'a str {
+        "locks:release"
+    }
+
+    fn description(&self) -> &'a str {
+        "Releases the locks of a running command, marking it as abandoned."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("id", ArgumentKind::STRING, "The id of the command log, as displayed by `locks:list`.").positional(),
+            CommandArgument::new("force", ArgumentKind::BOOL, "Skips the confirmation."),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let command_middleware = CommandMiddleware::new(db_conn.get_new_connection());
+
+        let id = match args.get_string("id") {
+            Some(id) => id,
+            None => io.ask_question("id", "Command log id:")?,
+        };
+
+        let log = command_middleware.get_by_id(&id).await?;
+
+        if log.status != CommandStatus::RUNNING {
+            bail!("command log {} is not running ({:?}).", id, log.status);
+        }
+
+        let lock = command_middleware.find_locks().await?.into_iter().find(|lock| log.id.as_ref() == Some(&lock.log));
+
+        // the owner may still be running, e.g. on another host.
+        if let Some(lock) = lock.filter(|lock| !lock.is_expired()) {
+            io.warning(&format!("The lease of {} is still active, its owner {} may still be running.", log.command_name, lock.owner));
+        }
+
+        if !args.get_bool("force") && !io.ask_confirm("confirm", &format!("Release the locks of {} ({})?", log.command_name, log.command_args), false)? {
+            io.warning("Release aborted.");
+            return Ok(());
+        }
+
+        // the run may have ended since it was looked up, its outcome is then kept.
+        if command_middleware.abandon_log(&log, &format!("lock released manually by {}.", lease::current_owner())).await?.is_none() {
+            io.warning(&format!("The run of {} ended meanwhile, nothing was abandoned.", log.command_name));
+            return Ok(());
+        }
+
+        command_middleware.release_lock(&log).await?;
+
+        io.success(&format!("Locks of {} released.", log.command_name));
+
+        Ok(())
+    }
+}
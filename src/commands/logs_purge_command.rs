@@ -0,0 +1,116 @@
+use anyhow::{Result, bail};
+use chrono::{Duration, Utc};
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+        retention::RetentionPolicy,
+    },
+    middlewares::command_middleware::CommandMiddleware,
+};
+
+#[derive(Clone, Default)]
+/// Purges the closed command logs, and their transcripts, according to a retention policy.
+///
+/// Args (the automatic retention policy applies if none of the policy args is given):
+/// - `keep_last`: the number of logs kept per command,
+/// - `days`: the max age of the logs, in days,
+/// - `error_days`: the max age of the failed logs, in days,
+/// - `dry_run`: flag to only preview the purged logs,
+/// - `force`: flag to skip the confirmation.
+pub struct LogsPurgeCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for LogsPurgeCommand {
+    fn name(&self) -> &'a str {
+        "logs:purge"
+    }
+
+    fn description(&self) -> &'a str {
+        "Purges the closed command logs, according to a retention policy."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::parallel().max_instances(1)
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("keep_last", ArgumentKind::INT, "The number of logs kept per command, the most recent ones."),
+            CommandArgument::new("days", ArgumentKind::INT, "Purges the logs older than this number of days."),
+            CommandArgument::new("error_days", ArgumentKind::INT, "Purges the failed logs older than this number of days, keeping them otherwise."),
+            CommandArgument::new("dry_run", ArgumentKind::BOOL, "Only previews the purged logs."),
+            CommandArgument::new("force", ArgumentKind::BOOL, "Skips the confirmation."),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let command_middleware = CommandMiddleware::new(db_conn.get_new_connection());
+
+        for name in ["keep_last", "days", "error_days"] {
+            if args.get_int(name).is_some_and(|value| value <= 0) {
+                bail!("{} must be positive.", name);
+            }
+        }
+
+        let given = RetentionPolicy {
+            keep_last: args.get_int("keep_last").map(|keep_last| keep_last as u32),
+            max_age: args.get_int("days").map(Duration::days),
+            failure_max_age: args.get_int("error_days").map(Duration::days),
+        };
+
+        // fallback on the automatic retention policy.
+        let policy = match given.is_empty() {
+            true => RetentionPolicy::from_env(),
+            false => Some(given),
+        };
+
+        let Some(policy) = policy else {
+            bail!("no retention policy: give keep_last, days or error_days, or set the HERMES_LOG_RETENTION_* variables.");
+        };
+
+        io.info(&format!("Retention policy: {}.", policy));
+
+        let purged = command_middleware.find_purgeable(&policy, Utc::now()).await?;
+
+        if purged.is_empty() {
+            io.info("No command log to purge.");
+            return Ok(());
+        }
+
+        if args.get_bool("dry_run") {
+            let rows = purged
+                .iter()
+                .map(|log| vec![
+                    log.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+                    log.command_name.clone(),
+                    log.command_args.clone(),
+                    format!("{:?}", log.status),
+                    log.created_at.to_rfc3339(),
+                ])
+                .collect::<Vec<Vec<String>>>();
+
+            io.table(
+                vec!["Log", "Command", "Args", "Status", "Started at"],
+                rows.iter().map(|row| row.iter().map(|cell| cell.as_str()).collect()).collect(),
+            );
+
+            io.info(&format!("{} command log(s) would be purged, run without dry_run to purge them.", purged.len()));
+            return Ok(());
+        }
+
+        if !args.get_bool("force") && !io.ask_confirm("confirm", &format!("Purge {} command log(s)?", purged.len()), false)? {
+            io.warning("Purge aborted.");
+            return Ok(());
+        }
+
+        command_middleware.delete_logs(&purged).await?;
+
+        io.success(&format!("{} command log(s) purged.", purged.len()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::command_middleware::CommandMiddleware,
+    model::command_transcript::TranscriptLevel,
+};
+
+#[derive(Clone, Default)]
+/// Shows a command log, then replays the transcript of its run.
+///
+/// Args:
+/// - `id`: the id of the command log (asked if missing).
+pub struct LogsShowCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for LogsShowCommand {
+    fn name(&self) -> &'a str {
+        "logs:show"
+    }
+
+    fn description(&self) -> &'a str {
+        "Shows a command log and replays the output of its run."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::parallel()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("id", ArgumentKind::STRING, "The id of the command log.").positional(),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let command_middleware = CommandMiddleware::new(db_conn.get_new_connection());
+
+        let id = match args.get_string("id") {
+            Some(id) => id,
+            None => io.ask_question("id", "Command log id:")?,
+        };
+
+        let log = command_middleware.get_by_id(&id).await?;
+
+        io.key_value_pair(vec![
+            ("Command", log.command_name.clone()),
+            ("Args", log.command_args.clone()),
+            ("Status", format!("{:?}", log.status)),
+            ("Attempt", log.attempt.to_string()),
+            ("Previous attempt", log.previous_attempt.as_ref().map(|id| id.id.to_raw()).unwrap_or("-".to_string())),
+            ("Owner", log.owner.as_ref().map(|owner| owner.to_string()).unwrap_or("unknown".to_string())),
+            ("Started at", log.created_at.to_rfc3339()),
+            ("Closed at", log.closed_at.map(|date| date.to_rfc3339()).unwrap_or("-".to_string())),
+            ("Elapsed", log.elapsed.map(|elapsed| format!("{:.3} secs", elapsed as f64 / 1000.0)).unwrap_or("-".to_string())),
+            ("Message", log.message.clone().unwrap_or("-".to_string())),
+        ]);
+
+        let Some(transcript) = command_middleware.find_transcript(&log).await? else {
+            io.warning("No transcript was saved for this run, it may still be running or have been killed.");
+            return Ok(());
+        };
+
+        io.section("Transcript");
+
+        // replay the lines with a console IO which does not record them, so they stay out of the transcript of this run.
+        let replay = ConsoleIO::new();
+
+        for line in transcript.lines.iter() {
+            match line.level {
+                TranscriptLevel::OUTPUT => replay.writeln(&line.text),
+                TranscriptLevel::SUCCESS => replay.success(&line.text),
+                TranscriptLevel::ERROR => replay.error(&line.text),
+                TranscriptLevel::WARNING => replay.warning(&line.text),
+                TranscriptLevel::NOTE => replay.note(&line.text),
+                TranscriptLevel::INFO => replay.info(&line.text),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, Duration, Utc};
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::command_middleware::CommandMiddleware,
+};
+
+#[derive(Clone, Default)]
+/// Reports statistics on the finished runs of each command, aggregated from the command logs.
+///
+/// Args:
+/// - `days`: only the runs of the last days are aggregated, all of them otherwise.
+pub struct LogsStatsCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for LogsStatsCommand {
+    fn name(&self) -> &'a str {
+        "logs:stats"
+    }
+
+    fn description(&self) -> &'a str {
+        "Reports statistics on the runs of each command: outcomes, elapsed times, last success and failure."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::parallel()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("days", ArgumentKind::INT, "Only aggregates the runs of the last days."),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let command_middleware = CommandMiddleware::new(db_conn.get_new_connection());
+
+        if args.get_int("days").is_some_and(|days| days <= 0) {
+            bail!("days must be positive.");
+        }
+
+        let since = args.get_int("days").map(|days| Utc::now() - Duration::days(days));
+        let stats = command_middleware.find_stats(since).await?;
+
+        let seconds = |milliseconds: i64| format!("{:.3}", milliseconds as f64 / 1000.0);
+        let date = |date: Option<DateTime<Utc>>| date.map(|date| date.to_rfc3339()).unwrap_or("-".to_string());
+
+        let rows = stats
+            .iter()
+            .map(|command_stats| vec![
+                command_stats.command_name.clone(),
+                command_stats.runs.to_string(),
+                format!("{:.1}%", command_stats.percent(command_stats.successes)),
+                format!("{:.1}%", command_stats.percent(command_stats.failures)),
+                format!("{:.1}%", command_stats.percent(command_stats.skipped)),
+                seconds(command_stats.p50_elapsed),
+                seconds(command_stats.p95_elapsed),
+                seconds(command_stats.max_elapsed),
+                date(command_stats.last_success_at),
+                date(command_stats.last_failure_at),
+            ])
+            .collect::<Vec<Vec<String>>>();
+
+        io.table(
+            vec!["Command", "Runs", "Success", "Errors", "Skipped", "p50 (secs)", "p95 (secs)", "Max (secs)", "Last success", "Last failure"],
+            rows.iter().map(|row| row.iter().map(|cell| cell.as_str()).collect()).collect(),
+        );
+
+        match since {
+            Some(since) => io.info(&format!("{} command(s) run since {}, errors include timeouts and abandoned runs.", stats.len(), since.to_rfc3339())),
+            None => io.info(&format!("{} command(s) run, errors include timeouts and abandoned runs.", stats.len())),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::CommandArgs, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::command_middleware::CommandMiddleware,
+};
+
+#[derive(Clone, Default)]
+/// Lists the running commands, with their progress.
+pub struct LogsStatusCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for LogsStatusCommand {
+    fn name(&self) -> &'a str {
+        "logs:status"
+    }
+
+    fn description(&self) -> &'a str {
+        "Lists the running commands, on any host, with their progress."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::parallel()
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, _args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let command_middleware = CommandMiddleware::new(db_conn.get_new_connection());
+
+        // this run is running as well.
+        let logs = command_middleware.find_running().await?
+            .into_iter()
+            .filter(|log| log.command_name != self.name())
+            .collect::<Vec<_>>();
+
+        let rows = logs
+            .iter()
+            .map(|log| vec![
+                log.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+                log.command_name.clone(),
+                log.command_args.clone(),
+                log.owner.as_ref().map(|owner| owner.to_string()).unwrap_or("unknown".to_string()),
+                log.created_at.to_rfc3339(),
+                log.progress.as_ref().map(|progress| progress.to_string()).unwrap_or("-".to_string()),
+                log.progress.as_ref().map(|progress| progress.updated_at.to_rfc3339()).unwrap_or("-".to_string()),
+            ])
+            .collect::<Vec<Vec<String>>>();
+
+        io.table(
+            vec!["Log", "Command", "Args", "Owner", "Started at", "Progress", "Progress at"],
+            rows.iter().map(|row| row.iter().map(|cell| cell.as_str()).collect()).collect(),
+        );
+
+        io.info(&format!("{} running command(s).", logs.len()));
+
+        Ok(())
+    }
+}
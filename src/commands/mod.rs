@@ -1 +1,25 @@
-pub mod test_command;
\ No newline at end of file
+pub mod test_command;
+pub mod list_command;
+pub mod help_command;
+pub mod sites_refresh_command;
+pub mod sites_visibility_command;
+pub mod api_users_create_command;
+pub mod api_users_list_command;
+pub mod api_users_revoke_command;
+pub mod users_create_command;
+pub mod users_list_command;
+pub mod users_passwd_command;
+pub mod users_status_command;
+pub mod users_delete_command;
+pub mod access_grant_command;
+pub mod access_revoke_command;
+pub mod access_list_command;
+pub mod schedule_list_command;
+pub mod schedule_add_command;
+pub mod schedule_remove_command;
+pub mod locks_list_command;
+pub mod locks_release_command;
+pub mod logs_show_command;
+pub mod logs_status_command;
+pub mod logs_purge_command;
+pub mod logs_stats_command;
\ No newline at end of file
@@ -0,0 +1,101 @@
+use anyhow::{Result, bail};
+use chrono::Utc;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        bootstrap::PreRuntimeErrors,
+        cli,
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_registry::CommandRegistry, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+        scheduler::{next_run, parse_cron},
+    },
+    middlewares::schedule_middleware::ScheduleMiddleware,
+};
+
+#[derive(Clone, Default)]
+/// Schedules a command, to be run by the server.
+///
+/// Args:
+/// - `command`: the name of the command (asked if missing),
+/// - `cron`: the cron expression, in UTC (asked if missing),
+/// - `args`: the arguments of the command, as typed in the console.
+pub struct ScheduleAddCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for ScheduleAddCommand {
+    fn name(&self) -> &'a str {
+        "schedule:add"
+    }
+
+    fn description(&self) -> &'a str {
+        "Schedules a command, to be run by the server."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("command", ArgumentKind::STRING, "The name of the command.").positional(),
+            CommandArgument::new("cron", ArgumentKind::STRING, "The cron expression, in UTC (e.g. `0 3 * * *`).").positional(),
+            CommandArgument::new("args", ArgumentKind::STRING, "The arguments of the command, as typed in the console (e.g. `--args=\"--force\"`)."),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let command_registry = rocket.state::<CommandRegistry>().unwrap();
+        let schedule_middleware = ScheduleMiddleware::new(db_conn.get_new_connection());
+
+        let command_name = match args.get_string("command") {
+            Some(command_name) => command_name,
+            None => io.ask_question("command", "Command:")?,
+        };
+
+        let possible_command = command_registry.get(&command_name);
+
+        if possible_command.is_none() {
+            let suggestions = command_registry.suggest(&command_name).iter().map(|name| name.to_string()).collect();
+            bail!(PreRuntimeErrors::FailedToGetCommand(command_name, suggestions));
+        }
+
+        let command = possible_command.unwrap();
+
+        let cron = match args.get_string("cron") {
+            Some(cron) => cron,
+            None => io.ask_question("cron", "Cron expression (UTC):")?,
+        };
+
+        parse_cron(&cron)?;
+
+        let possible_command_args = match args.get_string("args") {
+            Some(command_args) => shlex::split(&command_args),
+            None => Some(Vec::new()),
+        };
+
+        if possible_command_args.is_none() {
+            bail!("invalid command arguments: unbalanced quotes.");
+        }
+
+        // the arguments are checked now, rather than at each run.
+        let command_args = possible_command_args.unwrap();
+        CommandArgs::parse(&command.arguments(), &cli::normalize_args(command_args.clone()))?;
+
+        let schedule = schedule_middleware.create(command.name(), command_args, &cron).await?;
+
+        io.key_value_pair(vec![
+            ("Id", schedule.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default()),
+            ("Command", schedule.command_name.clone()),
+            ("Args", schedule.command_args.join(" ")),
+            ("Cron", schedule.cron.clone()),
+            ("Next run", next_run(&schedule, Utc::now()).map(|date| date.to_rfc3339()).unwrap_or("never".to_string())),
+        ]);
+
+        io.note("Scheduled commands are run by `hermes server`, all their arguments must be given as they can't be asked.");
+        io.success(&format!("Command {} scheduled.", schedule.command_name));
+
+        Ok(())
+    }
+}
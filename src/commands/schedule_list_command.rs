@@ -0,0 +1,61 @@
+use anyhow::Result;
+use chrono::Utc;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::CommandArgs, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+        scheduler::next_run,
+    },
+    middlewares::schedule_middleware::ScheduleMiddleware,
+};
+
+#[derive(Clone, Default)]
+/// Lists the scheduled commands.
+pub struct ScheduleListCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for ScheduleListCommand {
+    fn name(&self) -> &'a str {
+        "schedule:list"
+    }
+
+    fn description(&self) -> &'a str {
+        "Lists the scheduled commands, with their next run."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::parallel()
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, _args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let schedule_middleware = ScheduleMiddleware::new(db_conn.get_new_connection());
+
+        let schedules = schedule_middleware.find_all().await?;
+        let now = Utc::now();
+
+        let rows = schedules
+            .iter()
+            .map(|schedule| vec![
+                schedule.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default(),
+                schedule.command_name.clone(),
+                schedule.command_args.join(" "),
+                schedule.cron.clone(),
+                if schedule.enabled { "yes" } else { "no" }.to_string(),
+                schedule.last_tick_at.map(|date| date.to_rfc3339()).unwrap_or("never".to_string()),
+                next_run(schedule, now).map(|date| date.to_rfc3339()).unwrap_or("never".to_string()),
+            ])
+            .collect::<Vec<Vec<String>>>();
+
+        io.table(
+            vec!["Id", "Command", "Args", "Cron", "Enabled", "Last run", "Next run"],
+            rows.iter().map(|row| row.iter().map(|cell| cell.as_str()).collect()).collect(),
+        );
+
+        io.info(&format!("{} schedule(s).", schedules.len()));
+
+        Ok(())
+    }
+}
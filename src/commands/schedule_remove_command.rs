@@ -0,0 +1,54 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::schedule_middleware::ScheduleMiddleware,
+};
+
+#[derive(Clone, Default)]
+/// Removes a scheduled command.
+///
+/// Args (asked if missing):
+/// - `id`: the id of the schedule, as displayed by `schedule:list`.
+pub struct ScheduleRemoveCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for ScheduleRemoveCommand {
+    fn name(&self) -> &'a str {
+        "schedule:remove"
+    }
+
+    fn description(&self) -> &'a str {
+        "Removes a scheduled command."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("id", ArgumentKind::STRING, "The id of the schedule, as displayed by `schedule:list`.").positional(),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let schedule_middleware = ScheduleMiddleware::new(db_conn.get_new_connection());
+
+        let id = match args.get_string("id") {
+            Some(id) => id,
+            None => io.ask_question("id", "Schedule id:")?,
+        };
+
+        let schedule = schedule_middleware.delete(&id).await?;
+
+        io.success(&format!("Schedule {} of command {} removed.", id, schedule.command_name));
+
+        Ok(())
+    }
+}
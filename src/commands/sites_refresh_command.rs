@@ -0,0 +1,79 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::CommandArgs, command_concurrency::{ConcurrencyPolicy, SITES_GROUP}, command_context::CommandContext, command_retry::RetryPolicy, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+        sites_root::SitesRoot,
+    },
+    middlewares::site_middleware::{SiteChange, SiteMiddleware},
+};
+
+#[derive(Clone, Default)]
+/// Synchronizes the sites in database with the directories of the sites root.
+pub struct SitesRefreshCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for SitesRefreshCommand {
+    fn name(&self) -> &'a str {
+        "sites:refresh"
+    }
+
+    fn description(&self) -> &'a str {
+        "Synchronizes the sites in database with the directories of the sites root."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive().group(SITES_GROUP)
+    }
+
+    fn retry(&self) -> RetryPolicy {
+        RetryPolicy::attempts(3)
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, _args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let sites_root = rocket.state::<SitesRoot>().unwrap();
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let site_middleware = SiteMiddleware::new(db_conn.get_new_connection());
+
+        // scan the sites root.
+        io.step(1, 2, &format!("Scanning {}...", sites_root.path().display()));
+        let directories = sites_root.scan().await?;
+
+        // apply the changes in database.
+        io.step(2, 2, "Synchronizing sites...");
+        let report = site_middleware.refresh(&directories).await?;
+
+        let rows = report.changes
+            .iter()
+            .map(|(site, change)| vec![
+                site.subdomain.clone(),
+                site.path.clone(),
+                if site.enabled { "yes" } else { "no" }.to_string(),
+                site.visibility.as_str().to_string(),
+                format!("{:?}", change),
+            ])
+            .collect::<Vec<Vec<String>>>();
+
+        io.new_line();
+        io.table(
+            vec!["Subdomain", "Directory", "Enabled", "Visibility", "Change"],
+            rows.iter().map(|row| row.iter().map(|cell| cell.as_str()).collect()).collect(),
+        );
+
+        for warning in report.warnings.iter() {
+            io.warning(warning);
+        }
+
+        io.success(&format!(
+            "{} created, {} restored, {} missing, {} unchanged.",
+            report.count(SiteChange::CREATED),
+            report.count(SiteChange::RESTORED),
+            report.count(SiteChange::MISSING),
+            report.count(SiteChange::UNCHANGED),
+        ));
+
+        Ok(())
+    }
+}
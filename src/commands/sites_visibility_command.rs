@@ -0,0 +1,65 @@
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::{ConcurrencyPolicy, SITES_GROUP}, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::site_middleware::SiteMiddleware,
+    model::site::SiteVisibility,
+};
+
+#[derive(Clone, Default)]
+/// Changes who can access a site.
+///
+/// Args (asked if missing):
+/// - `subdomain`: the subdomain of the site,
+/// - `visibility`: `public` (anyone), `authenticated` (any logged in user) or `restricted` (granted users only).
+pub struct SitesVisibilityCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for SitesVisibilityCommand {
+    fn name(&self) -> &'a str {
+        "sites:visibility"
+    }
+
+    fn description(&self) -> &'a str {
+        "Changes who can access a site: anyone, any logged in user or granted users only."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive().group(SITES_GROUP).queue(Duration::from_secs(30))
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("subdomain", ArgumentKind::STRING, "The subdomain of the site.").positional(),
+            CommandArgument::new("visibility", ArgumentKind::ENUM(SiteVisibility::ALL.iter().map(|visibility| visibility.as_str()).collect()), "Who can access the site.").positional(),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let site_middleware = SiteMiddleware::new(db_conn.get_new_connection());
+
+        let subdomain = match args.get_string("subdomain") {
+            Some(subdomain) => subdomain,
+            None => io.ask_question("subdomain", "Subdomain of the site:")?,
+        };
+
+        let visibility = match args.get_string("visibility") {
+            Some(visibility) => visibility,
+            None => io.ask_select("visibility", "Visibility:", SiteVisibility::ALL.iter().map(|visibility| visibility.as_str()).collect())?,
+        };
+
+        let visibility = visibility.parse::<SiteVisibility>().map_err(|error| anyhow!(error))?;
+        let site = site_middleware.set_visibility(&subdomain, visibility).await?;
+
+        io.success(&format!("Site {} is now {}.", site.subdomain, site.visibility.as_str()));
+
+        Ok(())
+    }
+}
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use rocket::{Build, Rocket};
 
-use crate::core::commands::{command_trait::{CommandArgs, CommandTrait}, command_utils::ConsoleIO};
+use crate::core::commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO};
 
 #[derive(Clone, Default)]
 /// A simple test command.
@@ -17,17 +17,37 @@ impl<'a> CommandTrait<'a> for TestCommand {
         "A simple test command."
     }
 
-    fn is_parallel(&self) -> bool {
-        false
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive()
     }
 
-    async fn do_run(&self, _rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs) -> Result<()> {
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("error", ArgumentKind::BOOL, "Makes the command fail."),
+        ]
+    }
+
+    async fn do_run(&self, _rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, ctx: &CommandContext) -> Result<()> {
         io.info("Hello from test command!");
-        io.info(&format!("Args: {:?}", args));
+        io.info(&format!("Args: {}", args.to_canonical_string()));
+
+        let progress = ctx.progress(io, 30);
+        progress.set_message("waiting");
+
+        for _ in 0..30 {
+            // stop waiting when cancelled, e.g. with Ctrl-C.
+            tokio::select! {
+                _ = tokio::time::sleep(tokio::time::Duration::from_millis(100)) => progress.inc(1),
+                _ = ctx.cancelled() => {
+                    io.warning("Test command cancelled, cleaning up.");
+                    return Ok(());
+                }
+            }
+        }
 
-        tokio::time::sleep(tokio::time::Duration::from_secs(3)).await;
+        progress.finish();
 
-        if args.get("error").is_some() {
+        if args.get_bool("error") {
             return Err(anyhow::anyhow!("Oops !"));
         }
 
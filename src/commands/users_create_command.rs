@@ -0,0 +1,73 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::user_middleware::UserMiddleware,
+};
+
+#[derive(Clone, Default)]
+/// Creates a user.
+///
+/// Args (asked if missing):
+/// - `login`: the login of the user, usually an email,
+/// - `display_name`: the name displayed for the user, defaults to the login,
+/// - `password`: the password of the user.
+pub struct UsersCreateCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for UsersCreateCommand {
+    fn name(&self) -> &'a str {
+        "users:create"
+    }
+
+    fn description(&self) -> &'a str {
+        "Creates a user."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("login", ArgumentKind::STRING, "The login of the user, usually an email.").positional(),
+            CommandArgument::new("display_name", ArgumentKind::STRING, "The name displayed for the user, defaults to the login."),
+            CommandArgument::new("password", ArgumentKind::STRING, "The password of the user.").secret(),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+
+        let login = match args.get_string("login") {
+            Some(login) => login,
+            None => io.ask_question("login", "Login (email):")?,
+        };
+
+        let display_name = match args.get_string("display_name") {
+            Some(display_name) => display_name,
+            None => io.ask_question_default("display_name", "Display name:", &login)?,
+        };
+
+        let password = match args.get_string("password") {
+            Some(password) => password,
+            None => io.ask_password("password", "Password:")?,
+        };
+
+        let user = user_middleware.create(&login, &password, &display_name).await?;
+
+        io.key_value_pair(vec![
+            ("Login", user.login.clone()),
+            ("Display name", user.display_name.clone()),
+        ]);
+
+        io.success(&format!("User {} created.", user.login));
+
+        Ok(())
+    }
+}
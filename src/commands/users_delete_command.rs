@@ -0,0 +1,65 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::{site_access_middleware::SiteAccessMiddleware, user_middleware::UserMiddleware},
+};
+
+#[derive(Clone, Default)]
+/// Deletes a user and its site accesses.
+///
+/// Args:
+/// - `login`: the login of the user (asked if missing),
+/// - `force`: flag to skip the confirmation.
+pub struct UsersDeleteCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for UsersDeleteCommand {
+    fn name(&self) -> &'a str {
+        "users:delete"
+    }
+
+    fn description(&self) -> &'a str {
+        "Deletes a user."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("login", ArgumentKind::STRING, "The login of the user.").positional(),
+            CommandArgument::new("force", ArgumentKind::BOOL, "Skips the confirmation."),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+        let site_access_middleware = SiteAccessMiddleware::new(db_conn.get_new_connection());
+
+        let login = match args.get_string("login") {
+            Some(login) => login,
+            None => io.ask_question("login", "Login:")?,
+        };
+
+        let user = user_middleware.get_by_login(&login).await?;
+
+        if !args.get_bool("force") && !io.ask_confirm("confirm", &format!("Delete user {}?", user.login), false)? {
+            io.warning("Deletion aborted.");
+            return Ok(());
+        }
+
+        site_access_middleware.revoke_all(&user).await?;
+        user_middleware.delete(&user.login).await?;
+
+        io.success(&format!("User {} deleted.", user.login));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::CommandArgs, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::user_middleware::UserMiddleware,
+};
+
+#[derive(Clone, Default)]
+/// Lists the users.
+pub struct UsersListCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for UsersListCommand {
+    fn name(&self) -> &'a str {
+        "users:list"
+    }
+
+    fn description(&self) -> &'a str {
+        "Lists the users."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::parallel()
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, _args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+
+        let users = user_middleware.find_all().await?;
+
+        let rows = users
+            .iter()
+            .map(|user| vec![
+                user.login.clone(),
+                user.display_name.clone(),
+                if user.enabled { "yes" } else { "no" }.to_string(),
+                user.created_at.to_rfc3339(),
+                user.last_login_at.map(|date| date.to_rfc3339()).unwrap_or("never".to_string()),
+            ])
+            .collect::<Vec<Vec<String>>>();
+
+        io.table(
+            vec!["Login", "Display name", "Enabled", "Created at", "Last login at"],
+            rows.iter().map(|row| row.iter().map(|cell| cell.as_str()).collect()).collect(),
+        );
+
+        io.info(&format!("{} user(s).", users.len()));
+
+        Ok(())
+    }
+}
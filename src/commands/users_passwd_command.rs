@@ -0,0 +1,64 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::user_middleware::UserMiddleware,
+};
+
+#[derive(Clone, Default)]
+/// Changes the password of a user.
+///
+/// Args (asked if missing):
+/// - `login`: the login of the user,
+/// - `password`: the new password of the user.
+pub struct UsersPasswdCommand;
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for UsersPasswdCommand {
+    fn name(&self) -> &'a str {
+        "users:passwd"
+    }
+
+    fn description(&self) -> &'a str {
+        "Changes the password of a user."
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("login", ArgumentKind::STRING, "The login of the user.").positional(),
+            CommandArgument::new("password", ArgumentKind::STRING, "The new password of the user.").secret(),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+
+        let login = match args.get_string("login") {
+            Some(login) => login,
+            None => io.ask_question("login", "Login:")?,
+        };
+
+        // fail early if the user does not exist, before asking the password.
+        let user = user_middleware.get_by_login(&login).await?;
+
+        let password = match args.get_string("password") {
+            Some(password) => password,
+            None => io.ask_password("password", "New password:")?,
+        };
+
+        user_middleware.set_password(&user.login, &password).await?;
+
+        io.success(&format!("Password of user {} changed.", user.login));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use anyhow::Result;
+use rocket::{Build, Rocket};
+
+use crate::{
+    core::{
+        commands::{command_args::{ArgumentKind, CommandArgs, CommandArgument}, command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandTrait, command_utils::ConsoleIO},
+        database::{Connected, DatabaseState},
+    },
+    middlewares::user_middleware::UserMiddleware,
+};
+
+#[derive(Clone)]
+/// Enables or disables a user, registered as `users:enable` and `users:disable`.
+///
+/// Args (asked if missing):
+/// - `login`: the login of the user.
+pub struct UsersStatusCommand {
+    /// The status applied to the user.
+    enabled: bool,
+}
+
+impl UsersStatusCommand {
+    /// Creates the `users:enable` command.
+    pub fn enable() -> Self {
+        Self { enabled: true }
+    }
+
+    /// Creates the `users:disable` command.
+    pub fn disable() -> Self {
+        Self { enabled: false }
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a> CommandTrait<'a> for UsersStatusCommand {
+    fn name(&self) -> &'a str {
+        if self.enabled { "users:enable" } else { "users:disable" }
+    }
+
+    fn description(&self) -> &'a str {
+        if self.enabled {
+            "Enables a user, allowing them to log in."
+        } else {
+            "Disables a user, preventing them from logging in."
+        }
+    }
+
+    fn concurrency(&self) -> ConcurrencyPolicy {
+        ConcurrencyPolicy::exclusive()
+    }
+
+    fn arguments(&self) -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("login", ArgumentKind::STRING, "The login of the user.").positional(),
+        ]
+    }
+
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+        let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+        let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+
+        let login = match args.get_string("login") {
+            Some(login) => login,
+            None => io.ask_question("login", "Login:")?,
+        };
+
+        let user = user_middleware.set_enabled(&login, self.enabled).await?;
+
+        io.success(&format!("User {} {}.", user.login, if self.enabled { "enabled" } else { "disabled" }));
+
+        Ok(())
+    }
+}
@@ -1,12 +1,17 @@
-use std::collections::HashMap;
-
 use anyhow::{Result, bail};
 use rocket::{Build, Rocket};
 use thiserror::Error;
 
 use crate::core::commands::command_trait::CommandError;
 
-use super::commands::command_registry::CommandRegistry;
+use super::commands::{command_args::RawCommandArgs, command_registry::CommandRegistry, command_utils::ConsoleIO};
+use super::database::{Connected, DatabaseState};
+use super::repl;
+use super::retention::{LogsRetention, RetentionPolicy};
+use super::rocket_factory;
+use super::scheduler::Scheduler;
+use super::sites_root::SitesRoot;
+use super::sites_watcher::SitesWatcher;
 
 #[derive(Debug, Error)]
 /// Pre-runtime errors.
@@ -17,16 +22,53 @@ pub enum PreRuntimeErrors {
     FailedToLaunchRocketInstance(String),
     #[error("command registry not found in rocket state.")]
     FailedToGetCommandRegistry,
-    #[error("command {0} not found.")]
-    FailedToGetCommand(String),
+    #[error("command {0} not found.{}", did_you_mean(.1))]
+    FailedToGetCommand(String, Vec<String>),
     #[error("command {0} failed: {1}")]
     FailedToRunCommand(String, String),
+    #[error("{0}")]
+    InvalidCommandArguments(String),
     #[error("command skipped: {0}")]
     CommandSkipped(String),
+    #[error("{0}")]
+    CommandCancelled(String),
+    #[error("{0}")]
+    CommandTimedOut(String),
 }
 
 /// Launches the HTTP server.
 pub async fn launch_server(rocket: Rocket<Build>) -> Result<i32> {
+    // keep the sites in sync with the sites root while the server runs.
+    let sites_root = rocket.state::<SitesRoot>().unwrap().clone();
+    let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
+    let sites_watcher = SitesWatcher::new(sites_root, db_conn.get_new_connection());
+
+    tokio::spawn(async move {
+        if let Err(error) = sites_watcher.watch().await {
+            ConsoleIO::new().error(&format!("Sites watcher stopped: {}", error));
+        }
+    });
+
+    // purge the command logs periodically, if an automatic retention is configured.
+    if let Some(policy) = RetentionPolicy::from_env() {
+        let logs_retention = LogsRetention::new(policy, db_conn.get_new_connection());
+
+        tokio::spawn(async move {
+            if let Err(error) = logs_retention.run().await {
+                ConsoleIO::new().error(&format!("Logs retention stopped: {}", error));
+            }
+        });
+    }
+
+    // run the scheduled commands with a dedicated rocket instance, the server one being consumed by its launch, sharing its database connection.
+    let scheduler = Scheduler::new(rocket_factory::build_with(db_conn.clone())?);
+
+    tokio::spawn(async move {
+        if let Err(error) = scheduler.run().await {
+            ConsoleIO::new().error(&format!("Scheduler stopped: {}", error));
+        }
+    });
+
     let possible_ignited = rocket.ignite().await;
 
     if let Err(error) = &possible_ignited {
@@ -42,8 +84,15 @@ pub async fn launch_server(rocket: Rocket<Build>) -> Result<i32> {
     Ok(0)
 }
 
+/// Launches the interactive console.
+pub async fn launch_repl(rocket: Rocket<Build>) -> Result<i32> {
+    repl::run(&rocket).await?;
+
+    Ok(0)
+}
+
 /// Launches the console interface.
-pub async fn launch_console(rocket: Rocket<Build>, command: String, args: HashMap<String, Option<String>>) -> Result<i32>{
+pub async fn launch_console(rocket: Rocket<Build>, command: String, args: RawCommandArgs) -> Result<i32>{
     // get command registry
     let possible_command_registry = rocket.state::<CommandRegistry>();
 
@@ -58,7 +107,8 @@ pub async fn launch_console(rocket: Rocket<Build>, command: String, args: HashMa
 
     // check if command is present
     if possible_command.is_none() {
-        bail!(PreRuntimeErrors::FailedToGetCommand(command));
+        let suggestions = command_registry.suggest(&command).iter().map(|name| name.to_string()).collect();
+        bail!(PreRuntimeErrors::FailedToGetCommand(command, suggestions));
     }
     let command = possible_command.unwrap();
 
@@ -67,14 +117,35 @@ pub async fn launch_console(rocket: Rocket<Build>, command: String, args: HashMa
 
     // check if command failed
     if let Err(error) = &runtime {
-        let inner_error = error.root_cause().downcast_ref::<CommandError>().unwrap();
+        let inner_error = error.root_cause().downcast_ref::<CommandError>();
+
+        if let Some(CommandError::InvalidArguments(_, _)) = inner_error {
+            bail!(PreRuntimeErrors::InvalidCommandArguments(inner_error.unwrap().to_string()));
+        }
 
-        if matches!(inner_error, CommandError::AlreadyRunning(_, _)) {
-            bail!(PreRuntimeErrors::CommandSkipped(inner_error.to_string()));
+        if let Some(CommandError::AlreadyRunning(_, _) | CommandError::Locked(_, _)) = inner_error {
+            bail!(PreRuntimeErrors::CommandSkipped(inner_error.unwrap().to_string()));
+        }
+
+        if let Some(CommandError::Cancelled(_)) = inner_error {
+            bail!(PreRuntimeErrors::CommandCancelled(inner_error.unwrap().to_string()));
+        }
+
+        if let Some(CommandError::TimedOut(_, _)) = inner_error {
+            bail!(PreRuntimeErrors::CommandTimedOut(inner_error.unwrap().to_string()));
         }
 
         bail!(PreRuntimeErrors::FailedToRunCommand(command.name().to_string(), error.to_string()));
     }
 
     Ok(0)
+}
+
+/// Formats command suggestions, if any.
+fn did_you_mean(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        return String::new();
+    }
+
+    format!(" Did you mean {}? Use the `list` command to see all the commands.", suggestions.join(" or "))
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
-use std::collections::HashMap;
-use anyhow::Result;
-use clap::{builder::ValueParser, Parser, Subcommand};
+use std::path::PathBuf;
+
+use clap::{Parser, Subcommand};
+
+use super::commands::{command_args::RawCommandArgs, command_utils::OutputFormat};
 
 /// A struct representing the CLI.
 #[derive(Parser)]
@@ -9,6 +11,18 @@ pub struct Cli {
     /// Subcommand of the CLI
     #[command(subcommand)]
     pub subcommand: Command,
+    /// Format of the outputs, `json` printing one event per line and disabling prompts
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::TEXT)]
+    pub output: OutputFormat,
+    /// Disable colors, as does a non-empty `NO_COLOR` environment variable
+    #[arg(long, global = true)]
+    pub no_color: bool,
+    /// Answer prompts from the answers file and `HERMES_ANSWER_*` variables instead of asking, automatic when STDIN is not a terminal
+    #[arg(short = 'n', long, global = true)]
+    pub no_interaction: bool,
+    /// JSON file of prompt answers by question id, implying `--no-interaction`
+    #[arg(long, global = true, value_name = "FILE")]
+    pub answers: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -17,38 +31,88 @@ pub enum Command {
     Server,
     /// Start the console interface 
     Console {
-        /// Command to execute
-        console_command: String,
-        /// Arguments of the command
-        #[arg(num_args(0..), value_parser = ValueParser::new(parse_subcommand_args))]
-        args: HashMap<String, Option<String>>
+        /// Command to execute, an interactive shell is opened if missing
+        console_command: Option<String>,
+        /// Arguments of the command: `--key value`, `--key=value`, `--flag` or positional values
+        #[arg(num_args(0..), trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>
     },
 }
 
-/// This function will parse the arg string into a map formatted as KEY => Option(VALUE).
+/// Normalizes the arguments of a console command.
+///
+/// Arguments using the legacy `key=val;flag` format are converted to `--key=val --flag`, other arguments are kept as is.
+pub fn normalize_args(args: Vec<String>) -> RawCommandArgs {
+    args.into_iter()
+        .flat_map(|arg| {
+            if is_legacy_arg(&arg) {
+                parse_legacy_args(&arg)
+            } else {
+                vec![arg]
+            }
+        })
+        .collect()
+}
+
+/// Either an argument uses the legacy `key=val;flag` format or not.
+///
+/// Every `;` separated part must look like `name` or `name=value`, other values (e.g. a sentence with a `;`) are positional values kept as is. A bare word without `;` (e.g. `flag`) is ambiguous with a positional value, it is kept as is too.
+fn is_legacy_arg(arg: &str) -> bool {
+    if arg.starts_with('-') {
+        return false;
+    }
+
+    if !arg.contains(';') && !arg.contains('=') {
+        return false;
+    }
+
+    let is_name = |name: &str| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+
+    arg.split(';')
+        .filter(|part| !part.is_empty())
+        .all(|part| is_name(part.split_once('=').map(|(name, _)| name).unwrap_or(part)))
+}
+
+/// This function will parse the legacy arg string into `--key=val` and `--flag` arguments.
 ///
 /// The format of the args must be :
-/// - `key=val` for key-value pairs
+/// - `key=val` for key-value pairs, values may contain `=`
 /// - `flag` for flags only
 ///
 /// All separated by `;`
-fn parse_subcommand_args(arg_str: &str) -> Result<HashMap<String, Option<String>>> {
-    let mut args = HashMap::<String, Option<String>>::new();
+fn parse_legacy_args(arg_str: &str) -> RawCommandArgs {
+    arg_str
+        .split(';')
+        .filter(|arg_pack| !arg_pack.is_empty())
+        .map(|arg_pack| format!("--{}", arg_pack))
+        .collect()
+}
 
-    let arg_packs = arg_str.split(';').collect::<Vec<&str>>();
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    for arg_pack in arg_packs.iter() {
-        if arg_pack.contains('=') {
-            let arg_body = arg_pack.split('=').collect::<Vec<&str>>();
+    fn normalize(args: &[&str]) -> RawCommandArgs {
+        normalize_args(args.iter().map(|arg| arg.to_string()).collect())
+    }
 
-            let arg_name = arg_body.first().unwrap().to_string();
-            let arg_value = arg_body.get(1).unwrap().to_string();
+    #[test]
+    fn keeps_the_named_and_positional_args() {
+        assert_eq!(normalize(&["--url=a=b", "--key", "value", "--flag", "--", "jdoe"]), vec!["--url=a=b", "--key", "value", "--flag", "--", "jdoe"]);
+    }
 
-            args.insert(arg_name, Some(arg_value));
-        } else {
-            args.insert(arg_pack.to_string(), None);
-        }
+    #[test]
+    fn converts_the_legacy_args() {
+        assert_eq!(normalize(&["key=a=b;flag"]), vec!["--key=a=b", "--flag"]);
+        assert_eq!(normalize(&["login=jdoe;"]), vec!["--login=jdoe"]);
+        assert_eq!(normalize(&["force;"]), vec!["--force"]);
     }
 
-    Ok(args)
-}
\ No newline at end of file
+    #[test]
+    fn keeps_the_positional_values_which_are_not_legacy_args() {
+        assert_eq!(normalize(&["force"]), vec!["force"]);
+        assert_eq!(normalize(&["hello; world"]), vec!["hello; world"]);
+        assert_eq!(normalize(&["a=b;c d"]), vec!["a=b;c d"]);
+        assert_eq!(normalize(&["https://example.com/?a=b"]), vec!["https://example.com/?a=b"]);
+    }
+}
@@ -0,0 +1,449 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Display;
+
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use serde::Serialize;
+use thiserror::Error;
+
+/// shorthand type for raw command arguments, as given in the command line (e.g. `--key value`, `--key=value`, `--flag` or positional values).
+pub type RawCommandArgs = Vec<String>;
+
+/// The prefix of named arguments.
+const NAMED_PREFIX: &str = "--";
+
+/// The mask replacing secret argument values when serialized.
+const SECRET_MASK: &str = "********";
+
+/// The format of date arguments.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+#[derive(Debug, Error)]
+pub enum CommandArgsError {
+    #[error("invalid arguments:\n{}", .0.iter().map(|problem| format!("  - {}", problem)).collect::<Vec<String>>().join("\n"))]
+    InvalidArguments(Vec<String>),
+}
+
+/// The type of a command argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgumentKind {
+    /// Any text.
+    STRING,
+    /// A signed integer.
+    INT,
+    /// A flag, `true` when given without value.
+    BOOL,
+    /// A date, formatted as `YYYY-MM-DD`.
+    DATE,
+    /// One of the given values, case-insensitive.
+    ENUM(Vec<&'static str>),
+}
+
+impl Display for ArgumentKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ArgumentKind::STRING => write!(f, "string"),
+            ArgumentKind::INT => write!(f, "int"),
+            ArgumentKind::BOOL => write!(f, "bool"),
+            ArgumentKind::DATE => write!(f, "date"),
+            ArgumentKind::ENUM(values) => write!(f, "{}", values.join("|")),
+        }
+    }
+}
+
+/// The specification of a command argument.
+#[derive(Debug, Clone)]
+pub struct CommandArgument {
+    /// The name of the argument.
+    pub name: &'static str,
+    /// The type of the argument.
+    pub kind: ArgumentKind,
+    /// Either the argument must be given or not.
+    pub required: bool,
+    /// The value used when the argument is not given.
+    pub default: Option<&'static str>,
+    /// The help text of the argument.
+    pub help: &'static str,
+    /// Either the argument can be given by position or not, positional arguments are filled in declaration order.
+    pub positional: bool,
+    /// Either the value must be masked when serialized (passwords...) or not.
+    pub secret: bool,
+}
+
+impl CommandArgument {
+    /// Creates an optional argument without default value.
+    pub fn new(name: &'static str, kind: ArgumentKind, help: &'static str) -> Self {
+        Self {
+            name,
+            kind,
+            required: false,
+            default: None,
+            help,
+            positional: false,
+            secret: false,
+        }
+    }
+
+    /// Makes the argument required.
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Allows the argument to be given by position.
+    pub fn positional(mut self) -> Self {
+        self.positional = true;
+        self
+    }
+
+    /// Masks the value of the argument when serialized, e.g. in command logs.
+    pub fn secret(mut self) -> Self {
+        self.secret = true;
+        self
+    }
+
+    /// Sets the value used when the argument is not given.
+    pub fn with_default(mut self, default: &'static str) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Parses a raw value according to the argument type.
+    fn parse(&self, raw_value: Option<&str>) -> Result<ArgumentValue, String> {
+        // flags are the only arguments allowed without value.
+        let raw_value = match (raw_value, &self.kind) {
+            (None, ArgumentKind::BOOL) => return Ok(ArgumentValue::BOOL(true)),
+            (None, _) => return Err(format!("argument `{}` expects a value ({})", self.name, self.kind)),
+            (Some(raw_value), _) => raw_value.trim(),
+        };
+
+        match &self.kind {
+            ArgumentKind::STRING => Ok(ArgumentValue::STRING(raw_value.to_string())),
+            ArgumentKind::INT => raw_value
+                .parse::<i64>()
+                .map(ArgumentValue::INT)
+                .map_err(|_| format!("argument `{}` expects an int, got `{}`", self.name, raw_value)),
+            ArgumentKind::BOOL => match raw_value.to_lowercase().as_str() {
+                "true" | "yes" | "1" => Ok(ArgumentValue::BOOL(true)),
+                "false" | "no" | "0" => Ok(ArgumentValue::BOOL(false)),
+                _ => Err(format!("argument `{}` expects a bool, got `{}`", self.name, raw_value)),
+            },
+            ArgumentKind::DATE => NaiveDate::parse_from_str(raw_value, DATE_FORMAT)
+                .map(ArgumentValue::DATE)
+                .map_err(|_| format!("argument `{}` expects a date (YYYY-MM-DD), got `{}`", self.name, raw_value)),
+            ArgumentKind::ENUM(values) => values
+                .iter()
+                .find(|value| value.eq_ignore_ascii_case(raw_value))
+                .map(|value| ArgumentValue::STRING(value.to_string()))
+                .ok_or(format!("argument `{}` expects one of {}, got `{}`", self.name, values.join(", "), raw_value)),
+        }
+    }
+}
+
+/// A validated command argument value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum ArgumentValue {
+    STRING(String),
+    INT(i64),
+    BOOL(bool),
+    DATE(NaiveDate),
+}
+
+/// The arguments of a command, validated against its schema.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandArgs {
+    /// The values of the given arguments, and of the missing ones with a default value.
+    values: BTreeMap<String, ArgumentValue>,
+    /// The names of the arguments whose value is masked when serialized.
+    secrets: BTreeSet<String>,
+}
+
+impl CommandArgs {
+    /// Creates an empty set of arguments.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validates raw arguments against a command schema, applying the default values.
+    ///
+    /// All the unknown, missing and invalid arguments are reported at once.
+    pub fn parse(schema: &[CommandArgument], raw_args: &RawCommandArgs) -> Result<Self, CommandArgsError> {
+        let mut values = BTreeMap::new();
+        let mut secrets = BTreeSet::new();
+        let mut problems = Vec::new();
+
+        let raw_args = Self::match_schema(schema, raw_args, &mut problems);
+
+        for argument in schema.iter() {
+            let raw_value = match raw_args.get(argument.name) {
+                Some(raw_value) => raw_value.as_deref(),
+                None if argument.default.is_some() => argument.default,
+                None if argument.required => {
+                    problems.push(format!("missing required argument `{}` ({})", argument.name, argument.kind));
+                    continue;
+                }
+                None => continue,
+            };
+
+            match argument.parse(raw_value) {
+                Ok(value) => {
+                    if argument.secret {
+                        secrets.insert(argument.name.to_string());
+                    }
+
+                    values.insert(argument.name.to_string(), value);
+                }
+                Err(problem) => problems.push(problem),
+            }
+        }
+
+        if !problems.is_empty() {
+            return Err(CommandArgsError::InvalidArguments(problems));
+        }
+
+        Ok(Self { values, secrets })
+    }
+
+    /// Either raw arguments give a secret argument of a schema or not, e.g. to keep them out of the shell history.
+    pub fn contains_secret(schema: &[CommandArgument], raw_args: &RawCommandArgs) -> bool {
+        let raw_args = Self::match_schema(schema, raw_args, &mut Vec::new());
+
+        schema.iter().any(|argument| argument.secret && raw_args.contains_key(argument.name))
+    }
+
+    /// Matches the raw arguments with the arguments of a schema, returning their raw values by name.
+    fn match_schema(schema: &[CommandArgument], raw_args: &RawCommandArgs, problems: &mut Vec<String>) -> HashMap<String, Option<String>> {
+        let mut named = HashMap::<String, Option<String>>::new();
+        let mut positional = Vec::<&String>::new();
+        let mut tokens = raw_args.iter().peekable();
+
+        while let Some(token) = tokens.next() {
+            // everything after a lone `--` is positional.
+            if token == NAMED_PREFIX {
+                positional.extend(tokens.by_ref());
+                break;
+            }
+
+            let Some(named_token) = token.strip_prefix(NAMED_PREFIX) else {
+                positional.push(token);
+                continue;
+            };
+
+            let (name, inline_value) = match named_token.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (named_token, None),
+            };
+
+            let Some(argument) = schema.iter().find(|argument| argument.name == name) else {
+                problems.push(format!("unknown argument `{}`", name));
+                continue;
+            };
+
+            // flags never take the next token as value.
+            let value = match inline_value {
+                Some(value) => Some(value),
+                None if argument.kind == ArgumentKind::BOOL => None,
+                None => tokens.next_if(|next| !next.starts_with(NAMED_PREFIX)).cloned(),
+            };
+
+            named.insert(argument.name.to_string(), value);
+        }
+
+        // positional values fill the positional arguments not given by name.
+        let mut positional_arguments = schema
+            .iter()
+            .filter(|argument| argument.positional && !named.contains_key(argument.name))
+            .collect::<Vec<&CommandArgument>>()
+            .into_iter();
+
+        for value in positional {
+            match positional_arguments.next() {
+                Some(argument) => {
+                    named.insert(argument.name.to_string(), Some(value.clone()));
+                }
+                // flags of the legacy `key=value;flag` syntax are given without prefix.
+                None if schema.iter().any(|argument| argument.name == value && argument.kind == ArgumentKind::BOOL) => {
+                    named.insert(value.clone(), None);
+                }
+                None => problems.push(format!("unexpected argument `{}`", value)),
+            }
+        }
+
+        named
+    }
+
+    /// Either an argument has a value or not.
+    pub fn has(&self, name: &str) -> bool {
+        self.values.contains_key(name)
+    }
+
+    /// Gets the value of a string (or enum) argument.
+    pub fn get_string(&self, name: &str) -> Option<String> {
+        match self.values.get(name) {
+            Some(ArgumentValue::STRING(value)) => Some(value.clone()),
+            _ => None,
+        }
+    }
+
+    /// Gets the value of an int argument.
+    pub fn get_int(&self, name: &str) -> Option<i64> {
+        match self.values.get(name) {
+            Some(ArgumentValue::INT(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Gets the value of a bool argument, `false` if not given.
+    pub fn get_bool(&self, name: &str) -> bool {
+        matches!(self.values.get(name), Some(ArgumentValue::BOOL(true)))
+    }
+
+    /// Gets the value of a date argument, at midnight UTC.
+    pub fn get_date(&self, name: &str) -> Option<DateTime<Utc>> {
+        match self.values.get(name) {
+            Some(ArgumentValue::DATE(date)) => Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0).unwrap())),
+            _ => None,
+        }
+    }
+
+    /// Serializes the arguments in a canonical way (sorted by name, defaults applied), for lock purposes.
+    pub fn to_canonical_string(&self) -> String {
+        let values = self.values
+            .iter()
+            .map(|(name, value)| {
+                if self.secrets.contains(name) {
+                    (name, ArgumentValue::STRING(SECRET_MASK.to_string()))
+                } else {
+                    (name, value.clone())
+                }
+            })
+            .collect::<BTreeMap<&String, ArgumentValue>>();
+
+        serde_json::to_string(&values).unwrap_or("{}".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schema() -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("login", ArgumentKind::STRING, "The login.").positional(),
+            CommandArgument::new("password", ArgumentKind::STRING, "The password.").positional().secret(),
+        ]
+    }
+
+    fn raw_args(args: &[&str]) -> RawCommandArgs {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// The arguments of the validation tests.
+    fn typed_schema() -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("name", ArgumentKind::STRING, "The name.").required(),
+            CommandArgument::new("count", ArgumentKind::INT, "The count.").with_default("10"),
+            CommandArgument::new("since", ArgumentKind::DATE, "The date."),
+            CommandArgument::new("mode", ArgumentKind::ENUM(vec!["fast", "slow"]), "The mode.").with_default("fast"),
+            CommandArgument::new("token", ArgumentKind::STRING, "The token.").secret(),
+        ]
+    }
+
+    /// The problems reported for invalid arguments.
+    fn problems(args: &[&str]) -> Vec<String> {
+        match CommandArgs::parse(&typed_schema(), &raw_args(args)) {
+            Err(CommandArgsError::InvalidArguments(problems)) => problems,
+            Ok(_) => Vec::new(),
+        }
+    }
+
+    #[test]
+    fn applies_the_default_values() {
+        let args = CommandArgs::parse(&typed_schema(), &raw_args(&["--name", "test", "--mode", "SLOW", "--since", "2026-01-31"])).unwrap();
+
+        assert_eq!(args.get_int("count"), Some(10));
+        assert_eq!(args.get_string("mode").as_deref(), Some("slow"));
+        assert_eq!(args.get_date("since"), Some(Utc.with_ymd_and_hms(2026, 1, 31, 0, 0, 0).unwrap()));
+        assert!(!args.has("token"));
+    }
+
+    #[test]
+    fn reports_all_the_invalid_arguments_at_once() {
+        assert_eq!(problems(&["--count", "ten", "--since", "31/01/2026", "--mode", "medium", "--unknown"]), vec![
+            "unknown argument `unknown`",
+            "missing required argument `name` (string)",
+            "argument `count` expects an int, got `ten`",
+            "argument `since` expects a date (YYYY-MM-DD), got `31/01/2026`",
+            "argument `mode` expects one of fast, slow, got `medium`",
+        ]);
+    }
+
+    #[test]
+    fn canonical_strings_are_sorted_and_mask_the_secrets() {
+        let args = CommandArgs::parse(&typed_schema(), &raw_args(&["--token", "secret", "--name", "test"])).unwrap();
+        let reordered = CommandArgs::parse(&typed_schema(), &raw_args(&["--name=test", "--count", "10", "--token=other"])).unwrap();
+
+        assert_eq!(args.to_canonical_string(), r#"{"count":10,"mode":"fast","name":"test","token":"********"}"#);
+        assert_eq!(args.to_canonical_string(), reordered.to_canonical_string());
+    }
+
+    /// The arguments of the syntax tests.
+    fn syntax_schema() -> Vec<CommandArgument> {
+        vec![
+            CommandArgument::new("login", ArgumentKind::STRING, "The login.").positional(),
+            CommandArgument::new("url", ArgumentKind::STRING, "The url."),
+            CommandArgument::new("key", ArgumentKind::STRING, "The key."),
+            CommandArgument::new("force", ArgumentKind::BOOL, "The flag."),
+        ]
+    }
+
+    #[test]
+    fn keeps_the_equal_signs_of_inline_values() {
+        let args = CommandArgs::parse(&syntax_schema(), &raw_args(&["--url=a=b"])).unwrap();
+
+        assert_eq!(args.get_string("url").as_deref(), Some("a=b"));
+    }
+
+    #[test]
+    fn parses_named_values_and_flags() {
+        let args = CommandArgs::parse(&syntax_schema(), &raw_args(&["--key", "value", "--force", "jdoe"])).unwrap();
+
+        assert_eq!(args.get_string("key").as_deref(), Some("value"));
+        assert_eq!(args.get_string("login").as_deref(), Some("jdoe"));
+        assert!(args.get_bool("force"));
+    }
+
+    #[test]
+    fn parses_the_values_after_a_double_dash_as_positional() {
+        let args = CommandArgs::parse(&syntax_schema(), &raw_args(&["--", "--jdoe"])).unwrap();
+
+        assert_eq!(args.get_string("login").as_deref(), Some("--jdoe"));
+    }
+
+    #[test]
+    fn parses_the_legacy_args() {
+        let args = CommandArgs::parse(&syntax_schema(), &crate::core::cli::normalize_args(raw_args(&["key=a=b;force"]))).unwrap();
+
+        assert_eq!(args.get_string("key").as_deref(), Some("a=b"));
+        assert!(args.get_bool("force"));
+
+        // a bare legacy flag, once the positional arguments are filled.
+        let args = CommandArgs::parse(&syntax_schema(), &crate::core::cli::normalize_args(raw_args(&["jdoe", "force"]))).unwrap();
+
+        assert_eq!(args.get_string("login").as_deref(), Some("jdoe"));
+        assert!(args.get_bool("force"));
+    }
+
+    #[test]
+    fn finds_the_secrets_given_by_name_or_position() {
+        assert!(CommandArgs::contains_secret(&schema(), &raw_args(&["--password", "secret"])));
+        assert!(CommandArgs::contains_secret(&schema(), &raw_args(&["--password=secret"])));
+        assert!(CommandArgs::contains_secret(&schema(), &raw_args(&["jdoe", "secret"])));
+    }
+
+    #[test]
+    fn finds_no_secret_when_none_is_given() {
+        assert!(!CommandArgs::contains_secret(&schema(), &raw_args(&["jdoe"])));
+        assert!(!CommandArgs::contains_secret(&schema(), &raw_args(&["--login", "jdoe"])));
+    }
+}
@@ -0,0 +1,87 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+/// The exclusion group of the commands changing the sites, so they never run at the same time.
+pub const SITES_GROUP: &str = "sites";
+
+/// The concurrency policy of a command, e.g. which runs can't happen at the same time.
+///
+/// The policy is enforced with locks, held by a run from its beginning to its end.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConcurrencyPolicy {
+    /// Either a single run with the same args can happen at once or not.
+    pub exclusive_args: bool,
+    /// The max number of concurrent runs of the command, whatever their args.
+    pub max_instances: Option<u32>,
+    /// The exclusion groups of the command, a single run of all the commands of a group happening at once.
+    pub groups: Vec<&'static str>,
+    /// How long to wait for the locks to be released, instead of skipping the run.
+    pub queue_timeout: Option<Duration>,
+}
+
+impl ConcurrencyPolicy {
+    /// Runs can happen at the same time, without any lock.
+    pub fn parallel() -> Self {
+        Self::default()
+    }
+
+    /// A single run with the same args can happen at once.
+    pub fn exclusive() -> Self {
+        Self {
+            exclusive_args: true,
+            ..Self::default()
+        }
+    }
+
+    /// Limits the number of concurrent runs of the command, whatever their args.
+    pub fn max_instances(mut self, max_instances: u32) -> Self {
+        self.max_instances = Some(max_instances.max(1));
+        self
+    }
+
+    /// Adds the command to an exclusion group.
+    pub fn group(mut self, group: &'static str) -> Self {
+        self.groups.push(group);
+        self
+    }
+
+    /// Waits for the locks to be released, up to a timeout, instead of skipping the run.
+    pub fn queue(mut self, timeout: Duration) -> Self {
+        self.queue_timeout = Some(timeout);
+        self
+    }
+
+    /// Either runs need no lock or not.
+    pub fn is_parallel(&self) -> bool {
+        !self.exclusive_args && self.max_instances.is_none() && self.groups.is_empty()
+    }
+}
+
+impl Display for ConcurrencyPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_parallel() {
+            return write!(f, "parallel");
+        }
+
+        let mut rules = Vec::new();
+
+        if self.exclusive_args {
+            rules.push("exclusive by args".to_string());
+        }
+
+        if let Some(max_instances) = self.max_instances {
+            rules.push(format!("max {} at once", max_instances));
+        }
+
+        if !self.groups.is_empty() {
+            rules.push(format!("groups {}", self.groups.join(", ")));
+        }
+
+        match self.queue_timeout {
+            Some(timeout) => rules.push(format!("queued up to {} secs", timeout.as_secs())),
+            None => rules.push("skipped when locked".to_string()),
+        }
+
+        write!(f, "{}", rules.join(", "))
+    }
+}
@@ -0,0 +1,180 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::Utc;
+use indicatif::ProgressBar;
+use tokio::{sync::watch, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+
+use crate::{middlewares::command_middleware::CommandMiddleware, model::command_log::{CommandLog, CommandProgress}};
+
+use super::command_utils::ConsoleIO;
+
+/// The minimal delay between two saves of the progress in the command log.
+const PROGRESS_THROTTLE: Duration = Duration::from_secs(2);
+
+/// The context of a command run, given to the command logic.
+#[derive(Debug, Clone)]
+pub struct CommandContext {
+    /// Cancelled when the process receives SIGINT or SIGTERM, or when the command exceeds its max duration.
+    cancellation: CancellationToken,
+    /// The last progress reported by the command logic.
+    progress: Arc<watch::Sender<Option<CommandProgress>>>,
+}
+
+impl Default for CommandContext {
+    fn default() -> Self {
+        Self {
+            cancellation: CancellationToken::new(),
+            progress: Arc::new(watch::channel(None).0),
+        }
+    }
+}
+
+impl CommandContext {
+    /// Creates a new CommandContext.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Either the run was cancelled or not.
+    ///
+    /// Long running commands should check it between their steps, and return early when it is.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Waits until the run is cancelled, to be used with `tokio::select!`.
+    pub async fn cancelled(&self) {
+        self.cancellation.cancelled().await
+    }
+
+    /// Cancels the run.
+    pub fn cancel(&self) {
+        self.cancellation.cancel()
+    }
+
+    /// Cancels the run when the process receives SIGINT or SIGTERM, until the returned task is aborted.
+    pub fn cancel_on_signals(&self) -> JoinHandle<()> {
+        let cancellation = self.cancellation.clone();
+
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {},
+                _ = terminate_signal() => {},
+            }
+
+            cancellation.cancel();
+        })
+    }
+
+    /// Creates a progress bar, drawn on the terminal and saved in the command log so it can be followed from elsewhere.
+    pub fn progress(&self, io: &ConsoleIO, max: u64) -> CommandProgressBar {
+        let progress = CommandProgressBar {
+            bar: io.create_progress_bar(max),
+            max,
+            sender: self.progress.clone(),
+        };
+
+        progress.publish();
+        progress
+    }
+
+    /// Displays a step, saved as the progress of the command log.
+    pub fn step(&self, io: &ConsoleIO, nb: usize, max: usize, message: &str) {
+        io.step(nb, max, message);
+
+        self.progress.send_replace(Some(CommandProgress {
+            current: nb as u64,
+            max: max as u64,
+            message: Some(message.to_string()),
+            updated_at: Utc::now(),
+        }));
+    }
+
+    /// Returns the last progress reported by the command logic, if any.
+    pub fn last_progress(&self) -> Option<CommandProgress> {
+        self.progress.borrow().clone()
+    }
+
+    /// Saves the progress in the command log in background, at most every few seconds, until the returned task is aborted.
+    pub fn save_progress(&self, middleware: &CommandMiddleware, command_log: &CommandLog) -> JoinHandle<()> {
+        let middleware = middleware.clone();
+        let command_log = command_log.clone();
+        let mut receiver = self.progress.subscribe();
+
+        tokio::spawn(async move {
+            while receiver.changed().await.is_ok() {
+                let progress = receiver.borrow_and_update().clone();
+
+                // a failed save is retried with the next progress.
+                if let Some(progress) = progress {
+                    let _ = middleware.update_progress(&command_log, &progress).await;
+                }
+
+                tokio::time::sleep(PROGRESS_THROTTLE).await;
+            }
+        })
+    }
+}
+
+/// A progress bar of a command run, drawn on the terminal and saved in its command log.
+pub struct CommandProgressBar {
+    bar: ProgressBar,
+    max: u64,
+    sender: Arc<watch::Sender<Option<CommandProgress>>>,
+}
+
+impl CommandProgressBar {
+    /// Increments the progress.
+    pub fn inc(&self, delta: u64) {
+        self.bar.inc(delta);
+        self.publish();
+    }
+
+    /// Sets the progress.
+    pub fn set_position(&self, position: u64) {
+        self.bar.set_position(position);
+        self.publish();
+    }
+
+    /// Sets the message displayed along the progress.
+    pub fn set_message(&self, message: &str) {
+        self.bar.set_message(message.to_string());
+        self.publish();
+    }
+
+    /// Finishes the progress, leaving the bar on the terminal.
+    pub fn finish(&self) {
+        self.bar.finish();
+        self.publish();
+    }
+
+    /// Shares the progress with the command log.
+    fn publish(&self) {
+        let message = self.bar.message();
+
+        self.sender.send_replace(Some(CommandProgress {
+            current: self.bar.position(),
+            max: self.max,
+            message: if message.is_empty() { None } else { Some(message.to_string()) },
+            updated_at: Utc::now(),
+        }));
+    }
+}
+
+/// Waits for SIGTERM, e.g. a `docker stop`.
+#[cfg(unix)]
+async fn terminate_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    match signal(SignalKind::terminate()) {
+        Ok(mut terminate) => { terminate.recv().await; },
+        Err(_) => std::future::pending().await,
+    }
+}
+
+/// Waits forever, SIGTERM only existing on unix.
+#[cfg(not(unix))]
+async fn terminate_signal() {
+    std::future::pending().await
+}
@@ -12,6 +12,7 @@ pub struct CommandRegistry<'a> {
     pub commands: HashMap<&'a str, Box<dyn CommandTrait<'a>>>,
 }
 
+#[allow(clippy::new_without_default)]
 impl<'a> CommandRegistry<'a> {
     /// Create a new command registry.
     pub fn new() -> Self {
@@ -26,7 +27,97 @@ impl<'a> CommandRegistry<'a> {
     }
 
     /// Get a command from the registry.
-    pub fn get(&self, name: &str) -> Option<&Box<dyn CommandTrait<'a>>> {
-        self.commands.get(name)
+    pub fn get(&self, name: &str) -> Option<&dyn CommandTrait<'a>> {
+        self.commands.get(name).map(|command| command.as_ref())
     }
-}
\ No newline at end of file
+
+    /// Get all the commands of the registry, ordered by name.
+    pub fn all(&self) -> Vec<&dyn CommandTrait<'a>> {
+        let mut commands = self.commands
+            .values()
+            .map(|command| command.as_ref())
+            .collect::<Vec<&dyn CommandTrait<'a>>>();
+
+        commands.sort_by_key(|command| command.name());
+
+        commands
+    }
+
+    /// Get the names of the commands close to a name, e.g. to fix a typo, closest first.
+    pub fn suggest(&self, name: &str) -> Vec<&'a str> {
+        // allow about one typo every three characters.
+        let max_distance = (name.chars().count() / 3).max(2);
+
+        let mut suggestions = self.commands
+            .keys()
+            .map(|command_name| (edit_distance(name, command_name), *command_name))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .collect::<Vec<(usize, &'a str)>>();
+
+        suggestions.sort();
+
+        suggestions.into_iter().take(3).map(|(_, command_name)| command_name).collect()
+    }
+}
+
+/// Computes the Levenshtein distance between two strings, e.g. the number of single-character edits to go from one to the other.
+fn edit_distance(from: &str, to: &str) -> usize {
+    let to = to.chars().collect::<Vec<char>>();
+    let mut previous_row = (0..=to.len()).collect::<Vec<usize>>();
+
+    for (i, from_char) in from.chars().enumerate() {
+        let mut current_row = vec![i + 1];
+
+        for (j, to_char) in to.iter().enumerate() {
+            let substitution = previous_row[j] + usize::from(from_char != *to_char);
+            let insertion = current_row[j] + 1;
+            let deletion = previous_row[j + 1] + 1;
+
+            current_row.push(substitution.min(insertion).min(deletion));
+        }
+
+        previous_row = current_row;
+    }
+
+    previous_row[to.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::{help_command::HelpCommand, list_command::ListCommand, logs_show_command::LogsShowCommand, users_create_command::UsersCreateCommand, users_list_command::UsersListCommand};
+
+    fn registry() -> CommandRegistry<'static> {
+        let mut command_registry = CommandRegistry::new();
+        command_registry.register(Box::new(ListCommand));
+        command_registry.register(Box::new(HelpCommand));
+        command_registry.register(Box::new(UsersListCommand));
+        command_registry.register(Box::new(UsersCreateCommand));
+        command_registry.register(Box::new(LogsShowCommand));
+
+        command_registry
+    }
+
+    #[test]
+    fn computes_the_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("list", "list"), 0);
+        assert_eq!(edit_distance("", "list"), 4);
+        assert_eq!(edit_distance("lsit", "list"), 2);
+        assert_eq!(edit_distance("user:list", "users:list"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+    }
+
+    #[test]
+    fn suggests_the_closest_commands_first() {
+        assert_eq!(registry().suggest("user:list"), vec!["users:list"]);
+        assert_eq!(registry().suggest("users:lst"), vec!["users:list"]);
+        assert_eq!(registry().suggest("lst"), vec!["list"]);
+    }
+
+    #[test]
+    fn suggests_nothing_for_unrelated_names() {
+        assert!(registry().suggest("deploy").is_empty());
+        assert!(registry().suggest("sites:refresh").is_empty());
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt::Display;
+use std::time::Duration;
+
+use crate::middlewares::{
+    api_user_middleware::ApiUserMiddlewareError,
+    command_middleware::CommandMiddlewareError,
+    schedule_middleware::ScheduleMiddlewareError,
+    site_access_middleware::SiteAccessMiddlewareError,
+    site_middleware::SiteMiddlewareError,
+    user_middleware::UserMiddlewareError,
+};
+
+use super::command_trait::CommandError;
+
+/// The kinds of errors a failed attempt can be retried on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RetryableError {
+    /// the database failed, e.g. the connection was lost.
+    DATABASE,
+    /// the attempt exceeded the max duration of the command.
+    TIMEOUT,
+    /// any other error of the command logic.
+    OTHER,
+}
+
+impl RetryableError {
+    /// The kind of an error, none if it can never be retried (e.g. invalid arguments, a skipped or cancelled run).
+    pub fn of(error: &anyhow::Error) -> Option<Self> {
+        let root_cause = error.root_cause();
+
+        if let Some(command_error) = root_cause.downcast_ref::<CommandError>() {
+            return match command_error {
+                CommandError::DatabaseError(_) => Some(Self::DATABASE),
+                CommandError::TimedOut(_, _) => Some(Self::TIMEOUT),
+                _ => None,
+            };
+        }
+
+        let is_database_error = root_cause.is::<surrealdb::Error>()
+            || matches!(root_cause.downcast_ref(), Some(CommandMiddlewareError::DatabaseError(_)))
+            || matches!(root_cause.downcast_ref(), Some(SiteMiddlewareError::DatabaseError(_)))
+            || matches!(root_cause.downcast_ref(), Some(SiteAccessMiddlewareError::DatabaseError(_)))
+            || matches!(root_cause.downcast_ref(), Some(UserMiddlewareError::DatabaseError(_)))
+            || matches!(root_cause.downcast_ref(), Some(ApiUserMiddlewareError::DatabaseError(_)))
+            || matches!(root_cause.downcast_ref(), Some(ScheduleMiddlewareError::DatabaseError(_)));
+
+        match is_database_error {
+            true => Some(Self::DATABASE),
+            false => Some(Self::OTHER),
+        }
+    }
+}
+
+/// The retry policy of a command, e.g. how many times a failed run is attempted again.
+///
+/// Each attempt is a run of its own, with its locks and command log, linked to the log of the previous attempt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The max number of attempts, including the first one.
+    pub max_attempts: u32,
+    /// The delay before the first retry, doubled for each following one.
+    pub initial_backoff: Duration,
+    /// The max delay between two attempts.
+    pub max_backoff: Duration,
+    /// The kinds of errors retried.
+    pub retryable: Vec<RetryableError>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            retryable: vec![RetryableError::DATABASE],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A failed run is not retried.
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    /// A failed run is attempted up to a number of times, on database errors unless other kinds are given.
+    pub fn attempts(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the delay before the first retry, and the max delay between two attempts.
+    pub fn backoff(mut self, initial_backoff: Duration, max_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self.max_backoff = max_backoff.max(initial_backoff);
+        self
+    }
+
+    /// Sets the kinds of errors retried.
+    pub fn on(mut self, retryable: Vec<RetryableError>) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    /// Either an error of an attempt can be retried or not, whatever the number of attempts left.
+    pub fn is_retryable(&self, error: &anyhow::Error) -> bool {
+        RetryableError::of(error).is_some_and(|kind| self.retryable.contains(&kind))
+    }
+
+    /// The delay to wait after a failed attempt, exponential from the initial backoff.
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+
+        self.initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+impl Display for RetryPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.max_attempts <= 1 {
+            return write!(f, "none");
+        }
+
+        let retryable = self.retryable.iter().map(|kind| format!("{:?}", kind)).collect::<Vec<String>>();
+
+        write!(
+            f,
+            "{} attempts on {}, backoff {} to {} secs",
+            self.max_attempts,
+            retryable.join(", "),
+            self.initial_backoff.as_secs(),
+            self.max_backoff.as_secs(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_max_backoff() {
+        let retry = RetryPolicy::attempts(10).backoff(Duration::from_secs(1), Duration::from_secs(10));
+
+        let delays = (1..=6).map(|attempt| retry.delay_after(attempt).as_secs()).collect::<Vec<u64>>();
+
+        assert_eq!(delays, vec![1, 2, 4, 8, 10, 10]);
+        assert_eq!(retry.delay_after(u32::MAX), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn backoff_overflowing_falls_back_on_the_max_backoff() {
+        let huge = Duration::from_secs(u64::MAX / 2);
+        let retry = RetryPolicy::attempts(10).backoff(huge, huge);
+
+        assert_eq!(retry.delay_after(3), huge);
+    }
+
+    #[test]
+    fn skipped_and_invalid_runs_are_never_retried() {
+        let errors = [
+            anyhow!(CommandError::AlreadyRunning("test:retry".into(), "{}".into())),
+            anyhow!(CommandError::Locked("test:retry".into(), "group".into())),
+            anyhow!(CommandError::InvalidArguments("test:retry".into(), "missing argument".into())),
+            anyhow!(CommandError::Cancelled("test:retry".into())),
+        ];
+
+        for error in errors.iter() {
+            assert_eq!(RetryableError::of(error), None, "{} is retryable", error);
+        }
+    }
+
+    #[test]
+    fn classifies_the_retryable_errors() {
+        let database_errors = [
+            anyhow!(CommandError::DatabaseError("connection lost".into())),
+            anyhow!(CommandMiddlewareError::DatabaseError("connection lost".into())),
+            anyhow!(SiteMiddlewareError::DatabaseError("connection lost".into())).context("sites:refresh failed"),
+            anyhow!(ScheduleMiddlewareError::DatabaseError("connection lost".into())),
+        ];
+
+        for error in database_errors.iter() {
+            assert_eq!(RetryableError::of(error), Some(RetryableError::DATABASE), "{} is not a database error", error);
+        }
+
+        assert_eq!(RetryableError::of(&anyhow!(CommandError::TimedOut("test:retry".into(), 1))), Some(RetryableError::TIMEOUT));
+        assert_eq!(RetryableError::of(&anyhow!("unexpected failure")), Some(RetryableError::OTHER));
+        assert_eq!(RetryableError::of(&anyhow!(CommandMiddlewareError::NotFound("log".into()))), Some(RetryableError::OTHER));
+    }
+}
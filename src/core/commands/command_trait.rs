@@ -1,15 +1,19 @@
-use std::{collections::HashMap, str::FromStr};
+use std::time::Duration;
 
-use anyhow::{Result, bail};
+use anyhow::{Result, anyhow, bail};
 use rocket::{Rocket, Build};
 use thiserror::Error;
 
 use crate::{model::command_log::CommandLog, middlewares::command_middleware::{CommandMiddleware, CommandMiddlewareError}, core::database::{DatabaseState, Connected}};
 
-use super::command_utils::ConsoleIO;
+use super::command_args::{CommandArgs, CommandArgument, RawCommandArgs};
+use super::command_concurrency::ConcurrencyPolicy;
+use super::command_context::CommandContext;
+use super::command_retry::RetryPolicy;
+use super::command_utils::{ConsoleIO, Transcript};
 
-/// shorthand type for command arguments structure.
-pub type CommandArgs = HashMap<String, Option<String>>;
+/// How long a cancelled command logic can take to clean up before being dropped.
+const CANCELLATION_GRACE_PERIOD: Duration = Duration::from_secs(5);
 
 /// Final status of a command.
 pub enum CommandResult {
@@ -19,14 +23,26 @@ pub enum CommandResult {
     ERROR,
     /// the command was skipped due to external requirements, probably a lock race-condition.
     SKIPPED,
+    /// the command exceeded its max duration.
+    TIMEOUT,
+    /// the command was cancelled, by SIGINT or SIGTERM.
+    CANCELLED,
 }
 
 #[derive(Debug, Error)]
 pub enum CommandError {
+    #[error("command {0}: {1}")]
+    InvalidArguments(String, String),
     #[error("command {0} ({1}) is already running.")]
     AlreadyRunning(String, String),
+    #[error("command {0} skipped: {1}")]
+    Locked(String, String),
     #[error("database error: {0}")]
     DatabaseError(String),
+    #[error("command {0} was cancelled.")]
+    Cancelled(String),
+    #[error("command {0} exceeded its max duration of {1} secs.")]
+    TimedOut(String, u64),
 }
 
 #[async_trait::async_trait]
@@ -38,47 +54,75 @@ pub trait CommandTrait<'a>: Send + Sync {
     /// The description of the command.
     fn description(&self) -> &'a str;
 
-    /// Either the command can be launched in parallel or not using same parameters.
-    fn is_parallel(&self) -> bool;
+    /// The concurrency policy of the command, e.g. which runs can't happen at the same time.
+    fn concurrency(&self) -> ConcurrencyPolicy;
+
+    /// The arguments schema of the command.
+    ///
+    /// Arguments are validated against it before the command begins, unknown arguments are refused.
+    fn arguments(&self) -> Vec<CommandArgument> {
+        Vec::new()
+    }
+
+    /// The max duration of the command, after which it is cancelled with a TIMEOUT status.
+    fn max_duration(&self) -> Option<Duration> {
+        None
+    }
+
+    /// The retry policy of the command, failed runs are not retried by default.
+    fn retry(&self) -> RetryPolicy {
+        RetryPolicy::none()
+    }
 
     /// The command entrypoint.
     /// 
-    /// This will contain all the command logic (LLOC).
-    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs) -> Result<()>;
+    /// This will contain all the command logic (LLOC), which can observe cancellations through the context.
+    async fn do_run(&self, rocket: &Rocket<Build>, io: &ConsoleIO, args: &CommandArgs, ctx: &CommandContext) -> Result<()>;
 
     /// Starting phase of the command.
     /// 
     /// This will be called before the command execution.
     /// 
-    /// This will be used to aquire a lock on the command, if the command is not parallel, then declare the command log.
+    /// This will be used to aquire the locks of the command, according to its concurrency policy, then declare the command log.
     async fn begin(&self, middleware: &CommandMiddleware, args: &CommandArgs) -> Result<CommandLog> {
         // get args as string
         let args_as_str = self.get_args_as_str(args);
+        let mut log_id = None;
+
+        let concurrency = self.concurrency();
 
-        // if the command is not parallelizable, then try to aquire a lock.
-        if !self.is_parallel() {
-            let running = middleware.try_aquire_lock(&self.name(), &args_as_str).await;
+        // if the command is not parallelizable, then try to aquire its locks.
+        if !concurrency.is_parallel() {
+            let lock = middleware.acquire_locks(self.name(), &args_as_str, &concurrency).await;
 
-            if running.is_err() {
-                let inner = running.err().unwrap();
-                let inner_error = inner.root_cause().downcast_ref::<CommandMiddlewareError>().unwrap();
+            if let Err(error) = &lock {
+                let inner_error = error.root_cause().downcast_ref::<CommandMiddlewareError>();
 
-                if matches!(inner_error, CommandMiddlewareError::AlreadyRunning(_, _)) {
-                    let log = middleware.create_log(&self.name(), &args_as_str).await?;
+                if let Some(inner_error @ (CommandMiddlewareError::AlreadyRunning(_, _) | CommandMiddlewareError::LockUnavailable(_, _))) = inner_error {
+                    let log = middleware.create_log(self.name(), &args_as_str, None).await?;
                     self.end(middleware, log, CommandResult::SKIPPED, Some(inner_error.to_string())).await?;
-                    bail!(CommandError::AlreadyRunning(self.name().into(), args_as_str));
-                }
 
-                if matches!(inner_error, CommandMiddlewareError::DatabaseError(_)) {
-                    bail!(CommandError::DatabaseError(inner_error.to_string()));
+                    match inner_error {
+                        CommandMiddlewareError::AlreadyRunning(_, _) => bail!(CommandError::AlreadyRunning(self.name().into(), args_as_str)),
+                        _ => bail!(CommandError::Locked(self.name().into(), inner_error.to_string())),
+                    }
                 }
+
+                bail!(CommandError::DatabaseError(error.to_string()));
             }
+
+            log_id = Some(lock?);
         }
 
         // create the command log.
-        let log = middleware.create_log(&self.name(), &args_as_str).await;
+        let log = middleware.create_log(self.name(), &args_as_str, log_id.clone()).await;
 
         if let Err(error) = &log {
+            // the locks are released at once, rather than when their lease expires.
+            if let Some(log_id) = &log_id {
+                let _ = middleware.release_locks_of(log_id).await;
+            }
+
             let inner = error.root_cause().downcast_ref::<CommandMiddlewareError>().unwrap();
 
             if matches!(inner, CommandMiddlewareError::DatabaseError(_)) {
@@ -93,7 +137,7 @@ pub trait CommandTrait<'a>: Send + Sync {
     /// 
     /// This will be called after the command execution.
     /// 
-    /// This will be used to update the command log with the final status, error message and elapsed time.
+    /// This will be used to update the command log with the final status, error message and elapsed time, then release the locks of the command.
     async fn end(&self, middleware: &CommandMiddleware, command_log: CommandLog, command_result: CommandResult, message: Option<String>) -> Result<CommandLog> {
         // update the command log.
         let log = middleware.update_log(&command_log, command_result, message).await;
@@ -106,52 +150,366 @@ pub trait CommandTrait<'a>: Send + Sync {
             }
         }
 
+        // release the locks, unless they were taken over by other runs.
+        if !self.concurrency().is_parallel() {
+            if let Err(error) = middleware.release_lock(&command_log).await {
+                bail!(CommandError::DatabaseError(error.to_string()));
+            }
+        }
+
         Ok(log?)
     }
 
-    async fn run(&self, rocket: &Rocket<Build>, args: CommandArgs) -> Result<()> {
+    async fn run(&self, rocket: &Rocket<Build>, raw_args: RawCommandArgs) -> Result<()> {
         // getting requirements (console IO and database connection pool)
-        let io = rocket.state::<ConsoleIO>().unwrap();
+        let io = ConsoleIO::new();
         let db_conn = rocket.state::<DatabaseState<Connected>>().unwrap();
 
         // creating the command middleware
         let command_log_middleware = CommandMiddleware::new(db_conn.get_new_connection());
 
-        // display the command title
-        io.title(&self.name());
+        // validate the arguments against the command schema.
+        let possible_args = CommandArgs::parse(&self.arguments(), &raw_args);
+
+        if let Err(error) = &possible_args {
+            bail!(CommandError::InvalidArguments(self.name().into(), error.to_string()));
+        }
+
+        let args = possible_args?;
+        let retry = self.retry();
+        let mut previous_attempt: Option<CommandLog> = None;
+        let mut attempt = 1;
+
+        loop {
+            let (log, error) = match self.attempt(rocket, &command_log_middleware, &args, attempt, previous_attempt.as_ref()).await {
+                Ok((_, Ok(()))) => return Ok(()),
+                Ok((log, Err(error))) => (Some(log), error),
+                // the attempt could not begin, e.g. the database is unreachable.
+                Err(error) => (None, error),
+            };
+
+            if attempt >= retry.max_attempts || !retry.is_retryable(&error) {
+                return Err(error);
+            }
+
+            let delay = retry.delay_after(attempt);
+            io.warning(&format!("Attempt {}/{} of {} failed, retrying in {} secs.", attempt, retry.max_attempts, self.name(), delay.as_secs_f64()));
+
+            // wait before the next attempt, unless cancelled by SIGINT or SIGTERM.
+            let waiting = CommandContext::new();
+            let signals = waiting.cancel_on_signals();
+
+            let cancelled = tokio::select! {
+                _ = tokio::time::sleep(delay) => false,
+                _ = waiting.cancelled() => true,
+            };
+
+            signals.abort();
+
+            if cancelled {
+                io.warning(&format!("Retries of {} cancelled.", self.name()));
+                bail!(CommandError::Cancelled(self.name().into()));
+            }
+
+            // an attempt which could not begin has no log, the next one is linked to the last logged attempt.
+            previous_attempt = log.or(previous_attempt);
+            attempt += 1;
+        }
+    }
+
+    /// Runs a single attempt of the command, returning its command log and the outcome of the command logic.
+    ///
+    /// The attempt fails without a log when it could not begin, e.g. when the command is already running.
+    async fn attempt(&self, rocket: &Rocket<Build>, command_log_middleware: &CommandMiddleware, args: &CommandArgs, attempt: u32, previous_attempt: Option<&CommandLog>) -> Result<(CommandLog, Result<()>)> {
+        // console IO recording the transcript of the attempt.
+        let transcript = Transcript::new();
+        let io = &ConsoleIO::with_transcript(transcript.clone());
+
+        // display the command title, with the attempt number of retries.
+        match attempt {
+            1 => io.title(self.name()),
+            _ => io.title(&format!("{} (attempt {}/{})", self.name(), attempt, self.retry().max_attempts)),
+        }
+
         io.new_line();
 
         // start phase
-        let mut log = self.begin(&command_log_middleware, &args).await?;
+        let mut log = self.begin(command_log_middleware, args).await?;
+
+        // link the log to the previous attempt, so the history shows the full story.
+        if attempt > 1 {
+            log = match command_log_middleware.link_attempt(&log, attempt, previous_attempt).await {
+                Ok(linked) => linked,
+                Err(error) => {
+                    // close the log and release the locks, the attempt could not run.
+                    let _ = self.end(command_log_middleware, log, CommandResult::ERROR, Some(error.to_string())).await;
+                    return Err(error);
+                }
+            };
+        }
+
+        // cancel the command on SIGINT and SIGTERM.
+        let ctx = CommandContext::new();
+        let signals = ctx.cancel_on_signals();
+
+        // renew the lock lease while the command runs, cancelling it if the lease was lost.
+        let heartbeat = command_log_middleware.spawn_heartbeat(&log, &ctx);
+
+        // save the progress reported by the command logic, so it can be followed from elsewhere.
+        let progress = ctx.save_progress(command_log_middleware, &log);
+
+        // executes the command logic, until it ends, is cancelled or exceeds its max duration.
+        let exec = self.do_run(rocket, io, args, &ctx);
+        tokio::pin!(exec);
+
+        let max_duration = self.max_duration();
+        let deadline = async {
+            match max_duration {
+                Some(max_duration) => tokio::time::sleep(max_duration).await,
+                None => std::future::pending().await,
+            }
+        };
 
-        // executes the command logic
-        let exec_result = self.do_run(rocket, &io, &args).await;
+        let mut timed_out = false;
 
-        // if the command exited with an error, then update the command log with the error message.
+        let finished = tokio::select! {
+            result = &mut exec => Some(result),
+            _ = ctx.cancelled() => None,
+            _ = deadline => {
+                timed_out = true;
+                None
+            }
+        };
+
+        // let the command logic observe the cancellation, then drop it.
+        if finished.is_none() {
+            ctx.cancel();
+            let _ = tokio::time::timeout(CANCELLATION_GRACE_PERIOD, &mut exec).await;
+        }
+
+        signals.abort();
+        heartbeat.abort();
+        progress.abort();
+
+        // save the last progress, which may have been throttled.
+        if let Some(last_progress) = ctx.last_progress() {
+            let _ = command_log_middleware.update_progress(&log, &last_progress).await;
+        }
+
+        // a command returning early because it was cancelled is still cancelled.
+        let (command_result, exec_result) = match finished {
+            _ if timed_out => (
+                CommandResult::TIMEOUT,
+                Err(anyhow!(CommandError::TimedOut(self.name().into(), max_duration.unwrap_or_default().as_secs()))),
+            ),
+            Some(Ok(())) if !ctx.is_cancelled() => (CommandResult::SUCCESS, Ok(())),
+            Some(Err(error)) if !ctx.is_cancelled() => (CommandResult::ERROR, Err(error)),
+            _ => (CommandResult::CANCELLED, Err(anyhow!(CommandError::Cancelled(self.name().into())))),
+        };
+
+        // if the command did not succeed, then update the command log with the error message.
         if let Err(error) = &exec_result {
-            log = self.end(&command_log_middleware, log, CommandResult::ERROR, Some(error.to_string())).await?;
+            log = self.end(command_log_middleware, log, command_result, Some(error.to_string())).await?;
             io.error(&error.to_string());
         } else {
             // if the command exited with success, then update the command log with the success status.
-            log = self.end(&command_log_middleware, log, CommandResult::SUCCESS, None).await?;
+            log = self.end(command_log_middleware, log, command_result, None).await?;
         }
 
         // display the command status and elapsed time.
-        io.new_line();
         let elapsed = log.elapsed.unwrap() as f64 / 1000.0;
-        io.writeln(&format!("-- Status: {:?}, Elapsed: {:.3} secs --", &log.status, elapsed));
+        let log_id = log.id.as_ref().map(|id| id.id.to_raw()).unwrap_or_default();
+        io.summary(self.name(), &format!("{:?}", &log.status), elapsed, &log_id);
 
-        // if the command exited with an error, then return the error.
-        if exec_result.is_err() {
-            return Err(exec_result.err().unwrap());
+        // store the transcript along the command log, to investigate the run afterwards.
+        if let Err(error) = command_log_middleware.save_transcript(&log, transcript.lines()).await {
+            io.warning(&format!("Failed to save the transcript: {}", error));
         }
 
-        // return success.
-        Ok(())
+        Ok((log, exec_result))
     }
 
-    /// transforms a CommandArgs payload into a string, for lock purposes.
+    /// transforms a CommandArgs payload into a canonical string, for lock purposes.
     fn get_args_as_str(&self, args: &CommandArgs) -> String {
-        serde_json::to_string(&args).unwrap_or(String::from_str("{}").unwrap())
+        args.to_canonical_string()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tokio::sync::Barrier;
+
+    use super::*;
+    use crate::model::command_log::CommandStatus;
+
+    /// The number of runs started at once.
+    const CONCURRENT_RUNS: usize = 32;
+
+    #[derive(Clone, Default)]
+    struct LockedCommand;
+
+    #[async_trait::async_trait]
+    impl<'a> CommandTrait<'a> for LockedCommand {
+        fn name(&self) -> &'a str {
+            "test:locked"
+        }
+
+        fn description(&self) -> &'a str {
+            "A non parallel command."
+        }
+
+        fn concurrency(&self) -> ConcurrencyPolicy {
+            ConcurrencyPolicy::exclusive()
+        }
+
+        async fn do_run(&self, _rocket: &Rocket<Build>, _io: &ConsoleIO, _args: &CommandArgs, _ctx: &CommandContext) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct SlowCommand;
+
+    #[async_trait::async_trait]
+    impl<'a> CommandTrait<'a> for SlowCommand {
+        fn name(&self) -> &'a str {
+            "test:slow"
+        }
+
+        fn description(&self) -> &'a str {
+            "A command running longer than its max duration."
+        }
+
+        fn concurrency(&self) -> ConcurrencyPolicy {
+            ConcurrencyPolicy::parallel()
+        }
+
+        fn max_duration(&self) -> Option<Duration> {
+            Some(Duration::from_millis(100))
+        }
+
+        async fn do_run(&self, _rocket: &Rocket<Build>, _io: &ConsoleIO, _args: &CommandArgs, ctx: &CommandContext) -> Result<()> {
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(60)) => Ok(()),
+                _ = ctx.cancelled() => Ok(()),
+            }
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct CancelledCommand;
+
+    #[async_trait::async_trait]
+    impl<'a> CommandTrait<'a> for CancelledCommand {
+        fn name(&self) -> &'a str {
+            "test:cancelled"
+        }
+
+        fn description(&self) -> &'a str {
+            "A command cancelled while running."
+        }
+
+        fn concurrency(&self) -> ConcurrencyPolicy {
+            ConcurrencyPolicy::parallel()
+        }
+
+        async fn do_run(&self, _rocket: &Rocket<Build>, _io: &ConsoleIO, _args: &CommandArgs, ctx: &CommandContext) -> Result<()> {
+            ctx.cancel();
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn a_run_exceeding_its_max_duration_times_out() {
+        let middleware = CommandMiddleware::new(DatabaseState::connect_in_memory().await.get_new_connection());
+        let args = CommandArgs::parse(&[], &RawCommandArgs::new()).unwrap();
+
+        let (log, result) = SlowCommand.attempt(&rocket::build(), &middleware, &args, 1, None).await.unwrap();
+
+        assert_eq!(log.status, CommandStatus::TIMEOUT);
+        assert!(log.elapsed.is_some_and(|elapsed| (100..CANCELLATION_GRACE_PERIOD.as_millis() as i64).contains(&elapsed)));
+        assert!(matches!(result.unwrap_err().root_cause().downcast_ref::<CommandError>(), Some(CommandError::TimedOut(_, _))));
+    }
+
+    #[tokio::test]
+    async fn a_cancelled_run_is_cancelled_even_if_it_returns_early() {
+        let middleware = CommandMiddleware::new(DatabaseState::connect_in_memory().await.get_new_connection());
+        let args = CommandArgs::parse(&[], &RawCommandArgs::new()).unwrap();
+
+        let (log, result) = CancelledCommand.attempt(&rocket::build(), &middleware, &args, 1, None).await.unwrap();
+
+        assert_eq!(log.status, CommandStatus::CANCELLED);
+        assert!(log.elapsed.is_some());
+        assert!(matches!(result.unwrap_err().root_cause().downcast_ref::<CommandError>(), Some(CommandError::Cancelled(_))));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn concurrent_begins_acquire_the_lock_once() {
+        let middleware = CommandMiddleware::new(DatabaseState::connect_in_memory().await.get_new_connection());
+        let args = CommandArgs::parse(&[], &RawCommandArgs::new()).unwrap();
+        let barrier = Arc::new(Barrier::new(CONCURRENT_RUNS));
+
+        let runs = (0..CONCURRENT_RUNS)
+            .map(|_| {
+                let middleware = middleware.clone();
+                let args = args.clone();
+                let barrier = barrier.clone();
+
+                tokio::spawn(async move {
+                    barrier.wait().await;
+                    LockedCommand.begin(&middleware, &args).await
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut winners = Vec::new();
+
+        for run in runs {
+            match run.await.unwrap() {
+                Ok(log) => winners.push(log),
+                Err(error) => assert!(matches!(error.root_cause().downcast_ref::<CommandError>(), Some(CommandError::AlreadyRunning(_, _)))),
+            }
+        }
+
+        assert_eq!(winners.len(), 1);
+        assert_eq!(winners[0].status, CommandStatus::RUNNING);
+
+        let locks = middleware.find_locks().await.unwrap();
+        assert_eq!(locks.len(), 1);
+        assert_eq!(Some(&locks[0].log), winners[0].id.as_ref());
+
+        // ending the run releases the lock for the next one.
+        LockedCommand.end(&middleware, winners.remove(0), CommandResult::SUCCESS, None).await.unwrap();
+        assert!(middleware.find_locks().await.unwrap().is_empty());
+        assert!(LockedCommand.begin(&middleware, &args).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn concurrency_policies_share_their_locks() {
+        let middleware = CommandMiddleware::new(DatabaseState::connect_in_memory().await.get_new_connection());
+        let grouped = ConcurrencyPolicy::parallel().group("test");
+        let limited = ConcurrencyPolicy::parallel().max_instances(2);
+
+        // a group is exclusive across commands.
+        let first = middleware.acquire_locks("test:first", "", &grouped).await.unwrap();
+        let second = middleware.acquire_locks("test:second", "", &grouped).await;
+        assert!(matches!(second.unwrap_err().root_cause().downcast_ref::<CommandMiddlewareError>(), Some(CommandMiddlewareError::LockUnavailable(_, _))));
+
+        // a queued run gets the locks once released.
+        let queued = {
+            let middleware = middleware.clone();
+            tokio::spawn(async move { middleware.acquire_locks("test:second", "", &grouped.queue(Duration::from_secs(5))).await })
+        };
+
+        let log = middleware.create_log("test:first", "", Some(first)).await.unwrap();
+        middleware.release_lock(&log).await.unwrap();
+        assert!(queued.await.unwrap().is_ok());
+
+        // instances are limited whatever the args.
+        assert!(middleware.acquire_locks("test:limited", "--a", &limited).await.is_ok());
+        assert!(middleware.acquire_locks("test:limited", "--b", &limited).await.is_ok());
+        assert!(middleware.acquire_locks("test:limited", "--c", &limited).await.is_err());
+    }
+}
@@ -1,7 +1,14 @@
 #![allow(dead_code)]
 
 use std::collections::BTreeMap;
-
+use std::env;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+use anyhow::{Context, Result};
+use chrono::Utc;
 use comfy_table::{
     modifiers::UTF8_ROUND_CORNERS,
     presets::{NOTHING, UTF8_FULL},
@@ -10,6 +17,10 @@ use comfy_table::{
 use console::{style, Emoji, Term};
 use indicatif::ProgressBar;
 use inquire::{Confirm, DateSelect, MultiSelect, Password, PasswordDisplayMode, Select, Text};
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+use crate::model::command_transcript::{TranscriptLevel, TranscriptLine};
 
 /// separator for title outputs
 const HEAVY_SEPARATOR: &str = "==================================";
@@ -23,10 +34,137 @@ pub const LIST_SEPARATOR: &str = "$__SEPARATOR__$";
 /// real separator for lists
 const LIST_REAL_SEPARATOR: &str = "----------";
 
+/// the output format of the process, set once from the command line.
+static OUTPUT_FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// the interaction settings of the process, set once from the command line.
+static INTERACTION: OnceLock<Interaction> = OnceLock::new();
+
+/// prefix of the environment variables answering prompts, followed by the uppercased question id.
+const ANSWER_ENV_PREFIX: &str = "HERMES_ANSWER_";
+
+/// how prompts are answered.
+#[derive(Debug, Clone, Default)]
+struct Interaction {
+    /// either prompts are answered without the user or not.
+    non_interactive: bool,
+    /// the answers of the answers file, by question id.
+    answers: BTreeMap<String, String>,
+}
+
+/// format of the console outputs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// human readable, colored text.
+    #[default]
+    TEXT,
+    /// newline-delimited JSON events, for automation.
+    JSON,
+}
+
+#[derive(Debug, Error)]
+pub enum ConsoleIOError {
+    #[error("no answer to \"{1}\" in non-interactive mode: {}", answer_hint(.0))]
+    MissingAnswer(String, String),
+    #[error("invalid answer {1} to \"{0}\", expected {2}.")]
+    InvalidAnswer(String, String, String),
+}
+
+/// returns the environment variable answering a question.
+fn answer_env_var(id: &str) -> String {
+    let id = id.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' }).collect::<String>();
+
+    format!("{ANSWER_ENV_PREFIX}{id}")
+}
+
+/// returns how to answer a question in non-interactive mode, confirmations being skipped by the `force` argument of the commands.
+fn answer_hint(id: &str) -> String {
+    match id {
+        "confirm" => format!("pass the `force` argument, or answer it with {} or in the answers file.", answer_env_var(id)),
+        _ => format!("give it as the `{}` argument, with {} or in the answers file.", id, answer_env_var(id)),
+    }
+}
+
+/// configures the console outputs of the process, to be called once the command line is parsed.
+///
+/// Colors are disabled with `--no-color`, a non-empty `NO_COLOR` environment variable or the JSON output.
+pub fn configure_output(format: OutputFormat, no_color: bool) {
+    OUTPUT_FORMAT.get_or_init(|| format);
+
+    let no_color_env = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+
+    if no_color || no_color_env || format == OutputFormat::JSON {
+        console::set_colors_enabled(false);
+        console::set_colors_enabled_stderr(false);
+    }
+}
+
+/// configures how the prompts of the process are answered, to be called once the command line is parsed.
+///
+/// Prompts are answered without the user with `--no-interaction`, an answers file, or when STDIN is not a terminal. The answers file is a JSON object of answers by question id.
+pub fn configure_interaction(no_interaction: bool, answers_file: Option<&Path>) -> Result<()> {
+    let answers = match answers_file {
+        Some(answers_file) => {
+            let content = fs::read_to_string(answers_file)
+                .with_context(|| format!("failed to read the answers file {}", answers_file.display()))?;
+
+            serde_json::from_str::<BTreeMap<String, Value>>(&content)
+                .with_context(|| format!("invalid answers file {}", answers_file.display()))?
+                .into_iter()
+                .map(|(id, answer)| match answer {
+                    Value::String(answer) => (id, answer),
+                    answer => (id, answer.to_string()),
+                })
+                .collect()
+        }
+        None => BTreeMap::new(),
+    };
+
+    INTERACTION.get_or_init(|| Interaction {
+        non_interactive: no_interaction || answers_file.is_some() || !std::io::stdin().is_terminal(),
+        answers,
+    });
+
+    Ok(())
+}
+
+/// recorder of the lines printed by a command run, shared by the clones of its ConsoleIO.
+#[derive(Debug, Clone, Default)]
+pub struct Transcript {
+    lines: Arc<Mutex<Vec<TranscriptLine>>>,
+}
+
+impl Transcript {
+    /// creates an empty transcript
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// records a printed text, without its ANSI codes
+    pub fn record(&self, level: TranscriptLevel, text: &str) {
+        self.lines.lock().unwrap().push(TranscriptLine {
+            level,
+            text: console::strip_ansi_codes(text).to_string(),
+            printed_at: Utc::now(),
+        });
+    }
+
+    /// returns the recorded lines
+    pub fn lines(&self) -> Vec<TranscriptLine> {
+        self.lines.lock().unwrap().clone()
+    }
+}
+
 /// handler struct for standard outputs.
 pub struct ConsoleIO {
     stdout: Term,
     stderr: Term,
+    /// the format of the outputs.
+    format: OutputFormat,
+    /// how prompts are answered.
+    interaction: Interaction,
+    /// the transcript of the command run using this instance, if any.
+    transcript: Option<Transcript>,
 }
 
 #[allow(clippy::new_without_default)]
@@ -36,21 +174,91 @@ impl ConsoleIO {
         Self {
             stdout: Term::stdout(),
             stderr: Term::stderr(),
+            format: OUTPUT_FORMAT.get().copied().unwrap_or_default(),
+            interaction: INTERACTION.get().cloned().unwrap_or_default(),
+            transcript: None,
         }
     }
 
+    /// creates an instance of ConsoleIO recording its outputs in a transcript
+    pub fn with_transcript(transcript: Transcript) -> Self {
+        Self {
+            transcript: Some(transcript),
+            ..Self::new()
+        }
+    }
+
+    /// either the outputs are JSON events or not
+    pub fn is_json(&self) -> bool {
+        self.format == OutputFormat::JSON
+    }
+
+    /// either prompts are answered without the user or not, which is always the case with the JSON output
+    pub fn is_interactive(&self) -> bool {
+        !self.interaction.non_interactive && !self.is_json()
+    }
+
+    /// returns the answer to a question from the answers file or the environment, in non-interactive mode
+    fn answer(&self, id: &str) -> Option<String> {
+        self.interaction.answers
+            .get(id)
+            .cloned()
+            .or_else(|| env::var(answer_env_var(id)).ok())
+    }
+
+    /// records an output in the transcript, if any
+    fn record(&self, level: TranscriptLevel, text: &str) {
+        if let Some(transcript) = &self.transcript {
+            transcript.record(level, text);
+        }
+    }
+
+    /// writes a JSON event to STDOUT, on a single line
+    fn event(&self, event: Value) {
+        self.stdout.write_line(&event.to_string()).unwrap();
+    }
+
+    /// creates a table, without styles when colors are disabled
+    fn new_table() -> Table {
+        let mut table = Table::new();
+
+        if !console::colors_enabled() {
+            table.force_no_tty();
+        }
+
+        table
+    }
+
     /// writes to STDOUT without line return
     pub fn write(&self, text: &str) {
+        self.record(TranscriptLevel::OUTPUT, text);
+
+        if self.is_json() {
+            return self.event(json!({ "type": "text", "text": text }));
+        }
+
         self.stdout.write_str(text).unwrap();
     }
 
     /// writes to STDOUT with line return
     pub fn writeln(&self, text: &str) {
+        self.record(TranscriptLevel::OUTPUT, text);
+
+        if self.is_json() {
+            return self.event(json!({ "type": "text", "text": text }));
+        }
+
         self.stdout.write_line(text).unwrap();
     }
 
     /// writes to STDOUT with line return and bold font
     pub fn writeln_bold(&self, text: &str) {
+        self.record(TranscriptLevel::OUTPUT, text);
+
+        if self.is_json() {
+            return self.event(json!({ "type": "text", "text": text }));
+        }
+
         self.stdout
             .write_line(&format!("{}", style(text).white().bold()))
             .unwrap();
@@ -58,19 +266,38 @@ impl ConsoleIO {
 
     /// creates a new empty line in STDOUT
     pub fn new_line(&self) {
+        self.record(TranscriptLevel::OUTPUT, "");
+
+        // empty lines are only a matter of layout.
+        if self.is_json() {
+            return;
+        }
+
         self.stdout.write_line("").unwrap();
     }
 
     /// creates a title formatted output
     pub fn title(&self, title: &str) {
+        if self.is_json() {
+            self.record(TranscriptLevel::OUTPUT, &format!("--- {title} ---"));
+            return self.event(json!({ "type": "title", "text": title }));
+        }
+
         self.new_line();
         self.stdout
             .write_line(&format!("{}", style(format!("--- {title} ---")).yellow().bold()))
             .unwrap();
+        self.record(TranscriptLevel::OUTPUT, &format!("--- {title} ---"));
     }
 
     /// creates a section formatted output
     pub fn section(&self, title: &str) {
+        if self.is_json() {
+            self.record(TranscriptLevel::OUTPUT, title);
+            self.record(TranscriptLevel::OUTPUT, LIGHT_SEPARATOR);
+            return self.event(json!({ "type": "section", "text": title }));
+        }
+
         self.new_line();
         self.stdout
             .write_line(&format!("{}", style(title).cyan().bold()))
@@ -78,10 +305,18 @@ impl ConsoleIO {
         self.stdout
             .write_line(&format!("{}", style(LIGHT_SEPARATOR).cyan().bold()))
             .unwrap();
+        self.record(TranscriptLevel::OUTPUT, title);
+        self.record(TranscriptLevel::OUTPUT, LIGHT_SEPARATOR);
     }
 
     /// creates a comment formatted output
     pub fn comment(&self, comment: &str) {
+        self.record(TranscriptLevel::OUTPUT, &format!("// {comment}"));
+
+        if self.is_json() {
+            return self.event(json!({ "type": "comment", "text": comment }));
+        }
+
         self.stdout
             .write_line(&format!("// {}", style(comment).white().dim().bold()))
             .unwrap();
@@ -94,6 +329,12 @@ impl ConsoleIO {
     /// [1/4] doing things...
     /// ```
     pub fn step(&self, nb: usize, max: usize, message: &str) {
+        self.record(TranscriptLevel::OUTPUT, &format!("[{nb}/{max}] {message}"));
+
+        if self.is_json() {
+            return self.event(json!({ "type": "step", "current": nb, "max": max, "message": message }));
+        }
+
         let step_str = format!("[{nb}/{max}]");
 
         self.stdout
@@ -107,6 +348,12 @@ impl ConsoleIO {
 
     /// creates a success output
     pub fn success(&self, text: &str) {
+        self.record(TranscriptLevel::SUCCESS, text);
+
+        if self.is_json() {
+            return self.event(json!({ "type": "success", "message": text }));
+        }
+
         let success_symb_str = format!("[{} SUCCESS]", Emoji("✅", "✓"));
 
         self.stdout
@@ -120,6 +367,13 @@ impl ConsoleIO {
 
     /// creates a error output
     pub fn error(&self, text: &str) {
+        self.record(TranscriptLevel::ERROR, text);
+
+        // all the events go to STDOUT, so they can be read as a single stream.
+        if self.is_json() {
+            return self.event(json!({ "type": "error", "message": text }));
+        }
+
         let error_symb_str = format!("[{} ERROR]", Emoji("❌", "X"));
 
         self.stderr
@@ -133,6 +387,12 @@ impl ConsoleIO {
 
     /// creates a warning output
     pub fn warning(&self, text: &str) {
+        self.record(TranscriptLevel::WARNING, text);
+
+        if self.is_json() {
+            return self.event(json!({ "type": "warning", "message": text }));
+        }
+
         let warning_symb_str = format!("[{}  WARNING]", Emoji("⚠️", "!"));
 
         self.stdout
@@ -146,6 +406,12 @@ impl ConsoleIO {
 
     /// creates a note output
     pub fn note(&self, text: &str) {
+        self.record(TranscriptLevel::NOTE, text);
+
+        if self.is_json() {
+            return self.event(json!({ "type": "note", "message": text }));
+        }
+
         let note_symb_str = format!("[{} NOTE]", Emoji("📘", "🕮"));
 
         self.stdout
@@ -159,6 +425,12 @@ impl ConsoleIO {
 
     /// creates a info output
     pub fn info(&self, text: &str) {
+        self.record(TranscriptLevel::INFO, text);
+
+        if self.is_json() {
+            return self.event(json!({ "type": "info", "message": text }));
+        }
+
         let note_symb_str = format!("[{} INFO]", Emoji("📝", "▤"));
 
         self.stdout
@@ -172,14 +444,21 @@ impl ConsoleIO {
 
     /// creates a formatted (e.g. unordered) listing
     pub fn listing(&self, list: Vec<&str>) {
+        if self.is_json() {
+            list.iter().for_each(|item| self.record(TranscriptLevel::OUTPUT, &format!("• {item}")));
+            return self.event(json!({ "type": "listing", "items": list }));
+        }
+
         list.iter()
             .map(|item| self.writeln(&format!("• {item}")))
             .for_each(drop);
     }
 
     /// creates a data table with headers
+    ///
+    /// In JSON, rows are objects keyed by the headers.
     pub fn table(&self, headers: Vec<&str>, data: Vec<Vec<&str>>) {
-        let mut table = Table::new();
+        let mut table = Self::new_table();
 
         let header_bold = headers
             .iter()
@@ -190,42 +469,61 @@ impl ConsoleIO {
             })
             .collect::<Vec<Cell>>();
 
+        let rows = data
+            .iter()
+            .map(|row| headers.iter().zip(row.iter()).map(|(header, cell)| (header.to_string(), json!(cell))).collect::<Map<String, Value>>())
+            .collect::<Vec<Map<String, Value>>>();
+
         table
             .load_preset(UTF8_FULL)
             .apply_modifier(UTF8_ROUND_CORNERS)
             .set_header(header_bold)
             .add_rows(data);
 
+        if self.is_json() {
+            self.record(TranscriptLevel::OUTPUT, &format!("{table}"));
+            return self.event(json!({ "type": "table", "headers": headers, "rows": rows }));
+        }
+
         self.writeln(&format!("{table}"));
     }
 
     /// creates a key-value pair display.
     ///
-    /// You can use LIST_SEPARATOR to split your listings
+    /// You can use LIST_SEPARATOR to split your listings, each part being a distinct event in JSON.
     pub fn key_value_pair(&self, values: Vec<(&str, String)>) {
-        let mut table = Table::new();
+        let mut table = Self::new_table();
         table.load_preset(NOTHING);
 
+        let mut groups = vec![Map::new()];
+
         for (key, value) in values.iter() {
             if value == LIST_SEPARATOR {
                 table.add_row(vec![
                     Cell::new(LIST_REAL_SEPARATOR).add_attribute(Attribute::Bold),
                     Cell::new(""),
                 ]);
+                groups.push(Map::new());
             } else {
                 table.add_row(vec![
                     Cell::new(key).add_attribute(Attribute::Bold),
                     Cell::new(value),
                 ]);
+                groups.last_mut().unwrap().insert(key.to_string(), json!(value));
             }
         }
 
+        if self.is_json() {
+            self.record(TranscriptLevel::OUTPUT, &format!("{table}"));
+            return groups.into_iter().for_each(|values| self.event(json!({ "type": "key_value", "values": values })));
+        }
+
         self.writeln(&format!("{table}"));
     }
 
     /// creates a definition list
     pub fn definition_list(&self, values: BTreeMap<String, String>) {
-        let mut table = Table::new();
+        let mut table = Self::new_table();
         table.load_preset(NOTHING);
 
         let mut i = 0;
@@ -244,15 +542,33 @@ impl ConsoleIO {
             }
         }
 
+        if self.is_json() {
+            self.record(TranscriptLevel::OUTPUT, &format!("{table}"));
+            return self.event(json!({ "type": "definition_list", "values": values }));
+        }
+
         self.writeln(&format!("{table}"));
     }
 
+    /// creates the summary of a command run, ending its output.
+    pub fn summary(&self, command: &str, status: &str, elapsed: f64, log_id: &str) {
+        let text = format!("-- Status: {}, Elapsed: {:.3} secs, Log: {} --", status, elapsed, log_id);
+        self.record(TranscriptLevel::OUTPUT, &text);
+
+        if self.is_json() {
+            return self.event(json!({ "type": "summary", "command": command, "status": status, "elapsed": elapsed, "log": log_id }));
+        }
+
+        self.new_line();
+        self.stdout.write_line(&text).unwrap();
+    }
+
     /// shorthand method to create a text question for the user.
     ///
     /// **Note:** You must use the `prompt()` method to actually display it to the user.
     ///
     /// See https://github.com/mikaelmello/inquire
-    pub fn input_text<'a>(&'a self, question: &'a str) -> Text {
+    pub fn input_text<'a>(&'a self, question: &'a str) -> Text<'a> {
         Text::new(question)
     }
 
@@ -261,7 +577,7 @@ impl ConsoleIO {
     /// **Note:** You must use the `prompt()` method to actually display it to the user.
     ///
     /// See https://github.com/mikaelmello/inquire
-    pub fn input_date<'a>(&'a self, question: &'a str) -> DateSelect {
+    pub fn input_date<'a>(&'a self, question: &'a str) -> DateSelect<'a> {
         DateSelect::new(question)
     }
 
@@ -270,7 +586,7 @@ impl ConsoleIO {
     /// **Note:** You must use the `prompt()` method to actually display it to the user.
     ///
     /// See https://github.com/mikaelmello/inquire
-    pub fn input_select<'a>(&'a self, question: &'a str, choices: Vec<&'a str>) -> Select<&'a str> {
+    pub fn input_select<'a>(&'a self, question: &'a str, choices: Vec<&'a str>) -> Select<'a, &'a str> {
         Select::new(question, choices)
     }
 
@@ -283,7 +599,7 @@ impl ConsoleIO {
         &'a self,
         question: &'a str,
         choices: Vec<&'a str>,
-    ) -> MultiSelect<&'a str> {
+    ) -> MultiSelect<'a, &'a str> {
         MultiSelect::new(question, choices)
     }
 
@@ -292,7 +608,7 @@ impl ConsoleIO {
     /// **Note:** You must use the `prompt()` method to actually display it to the user.
     ///
     /// See https://github.com/mikaelmello/inquire
-    pub fn input_confirm<'a>(&'a self, question: &'a str) -> Confirm {
+    pub fn input_confirm<'a>(&'a self, question: &'a str) -> Confirm<'a> {
         Confirm::new(question)
     }
 
@@ -301,12 +617,18 @@ impl ConsoleIO {
     /// **Note:** You must use the `prompt()` method to actually display it to the user.
     ///
     /// See https://github.com/mikaelmello/inquire
-    pub fn input_password<'a>(&'a self, question: &'a str) -> Password {
+    pub fn input_password<'a>(&'a self, question: &'a str) -> Password<'a> {
         Password::new(question)
     }
 
     /// shorthand method to directly ask a question to the user.
-    pub fn ask_question(&self, question: &str) -> String {
+    ///
+    /// In non-interactive mode, the answer is looked for by question id, failing if missing.
+    pub fn ask_question(&self, id: &str, question: &str) -> Result<String, ConsoleIOError> {
+        if !self.is_interactive() {
+            return self.answer(id).ok_or(ConsoleIOError::MissingAnswer(id.to_string(), question.to_string()));
+        }
+
         let mut response = self.input_text(question).prompt();
 
         while response.is_err() {
@@ -315,11 +637,17 @@ impl ConsoleIO {
             response = Text::new(question).prompt();
         }
 
-        response.unwrap()
+        Ok(response.unwrap())
     }
 
     /// shorthand method to directly ask a question to the user with a default response.
-    pub fn ask_question_default(&self, question: &str, default: &str) -> String {
+    ///
+    /// In non-interactive mode, the default is used when no answer is found.
+    pub fn ask_question_default(&self, id: &str, question: &str, default: &str) -> Result<String, ConsoleIOError> {
+        if !self.is_interactive() {
+            return Ok(self.answer(id).unwrap_or(default.to_string()));
+        }
+
         let mut response = self.input_text(question).with_default(default).prompt();
 
         while response.is_err() {
@@ -328,24 +656,41 @@ impl ConsoleIO {
             response = Text::new(question).prompt();
         }
 
-        response.unwrap()
+        Ok(response.unwrap())
     }
 
     /// shorthand method to directly ask a confirmation to the user.
-    pub fn ask_confirm(&self, question: &str) -> bool {
-        let mut response = self.input_confirm(question).with_default(true).prompt();
+    ///
+    /// In non-interactive mode, the default is used when no answer is found.
+    pub fn ask_confirm(&self, id: &str, question: &str, default: bool) -> Result<bool, ConsoleIOError> {
+        if !self.is_interactive() {
+            return match self.answer(id).map(|answer| answer.to_lowercase()) {
+                None => Ok(default),
+                Some(answer) if ["y", "yes", "true", "1"].contains(&answer.as_str()) => Ok(true),
+                Some(answer) if ["n", "no", "false", "0"].contains(&answer.as_str()) => Ok(false),
+                Some(answer) => Err(ConsoleIOError::InvalidAnswer(question.to_string(), answer, "yes or no".to_string())),
+            };
+        }
+
+        let mut response = self.input_confirm(question).with_default(default).prompt();
 
         while response.is_err() {
             self.error("An error occured while data input, please try again");
 
-            response = self.input_confirm(question).prompt();
+            response = self.input_confirm(question).with_default(default).prompt();
         }
 
-        response.unwrap()
+        Ok(response.unwrap())
     }
 
     /// shorthand method to directly ask a password to the user.
-    pub fn ask_password(&self, question: &str) -> String {
+    ///
+    /// In non-interactive mode, the answer is looked for by question id, failing if missing.
+    pub fn ask_password(&self, id: &str, question: &str) -> Result<String, ConsoleIOError> {
+        if !self.is_interactive() {
+            return self.answer(id).ok_or(ConsoleIOError::MissingAnswer(id.to_string(), question.to_string()));
+        }
+
         let mut response = self
             .input_password(question)
             .with_display_mode(PasswordDisplayMode::Masked)
@@ -360,7 +705,32 @@ impl ConsoleIO {
                 .prompt();
         }
 
-        response.unwrap()
+        Ok(response.unwrap())
+    }
+
+    /// shorthand method to directly ask the user to choose among choices.
+    ///
+    /// In non-interactive mode, the answer is looked for by question id, failing if missing or not among the choices.
+    pub fn ask_select(&self, id: &str, question: &str, choices: Vec<&str>) -> Result<String, ConsoleIOError> {
+        if !self.is_interactive() {
+            let answer = self.answer(id).ok_or(ConsoleIOError::MissingAnswer(id.to_string(), question.to_string()))?;
+
+            if !choices.contains(&answer.as_str()) {
+                return Err(ConsoleIOError::InvalidAnswer(question.to_string(), answer, choices.join(", ")));
+            }
+
+            return Ok(answer);
+        }
+
+        let mut response = self.input_select(question, choices.clone()).prompt();
+
+        while response.is_err() {
+            self.error("An error occured while data input, please try again");
+
+            response = self.input_select(question, choices.clone()).prompt();
+        }
+
+        Ok(response.unwrap().to_string())
     }
 
     /// creates a progress bar for the user.
@@ -370,6 +740,10 @@ impl ConsoleIO {
     ///
     /// See https://docs.rs/indicatif/latest/indicatif/struct.ProgressBar.html
     pub fn create_progress_bar(&self, max: u64) -> ProgressBar {
+        if self.is_json() {
+            return ProgressBar::hidden();
+        }
+
         ProgressBar::new(max)
     }
 
@@ -379,6 +753,10 @@ impl ConsoleIO {
     ///
     /// See https://docs.rs/indicatif/latest/indicatif/struct.ProgressBar.html
     pub fn create_spinner(&self) -> ProgressBar {
+        if self.is_json() {
+            return ProgressBar::hidden();
+        }
+
         ProgressBar::new_spinner()
     }
 }
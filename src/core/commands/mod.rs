@@ -1,3 +1,7 @@
 pub mod command_utils;
+pub mod command_args;
+pub mod command_concurrency;
+pub mod command_retry;
+pub mod command_context;
 pub mod command_trait;
 pub mod command_registry;
\ No newline at end of file
@@ -2,7 +2,7 @@ use std::env;
 use std::marker::PhantomData;
 
 use surrealdb::Surreal;
-use surrealdb::engine::remote::ws::{Ws, Client};
+use surrealdb::engine::any::{self, Any};
 use anyhow::{Result, Context};
 use surrealdb::opt::auth::Root;
 
@@ -20,7 +20,7 @@ pub struct Connected;
 /// This struct is generic over the state of the connection.
 #[derive(Debug)]
 pub struct DatabaseState<T = Disconnected> {
-    conn: Surreal<Client>,
+    conn: Surreal<Any>,
     state: PhantomData<T>
 }
 
@@ -43,8 +43,8 @@ impl DatabaseState<Disconnected> {
         let namespace = env::var("SDB_NAMESPACE").unwrap_or("surreal".to_string());
         let database = env::var("SDB_DB").unwrap_or("surreal".to_string());
 
-        // Connect to the database.
-        let conn = Surreal::new::<Ws>(format!("{}:{}", host, port)).await?;
+        // Connect to the database, the engine being chosen from the endpoint scheme.
+        let conn = any::connect(format!("ws://{}:{}", host, port)).await?;
 
         // Sign in to the database.
         conn.signin(Root {
@@ -62,11 +62,33 @@ impl DatabaseState<Disconnected> {
             state: PhantomData
         })
     }
+
+    /// Connects to an empty in-memory database, for the tests.
+    #[cfg(test)]
+    pub async fn connect_in_memory() -> DatabaseState<Connected> {
+        let conn = any::connect("mem://").await.unwrap();
+        conn.use_ns("test").use_db("test").await.unwrap();
+
+        DatabaseState {
+            conn,
+            state: PhantomData
+        }
+    }
+}
+
+/// Sharing the connected state, e.g. between the server and its background tasks, shares the connection.
+impl Clone for DatabaseState<Connected> {
+    fn clone(&self) -> Self {
+        DatabaseState {
+            conn: self.conn.clone(),
+            state: PhantomData
+        }
+    }
 }
 
 impl DatabaseState<Connected> {
     /// Returns a clone of the connection.
-    pub fn get_new_connection(&self) -> Surreal<Client> {
+    pub fn get_new_connection(&self) -> Surreal<Any> {
         self.conn.clone()
     }
 }
\ No newline at end of file
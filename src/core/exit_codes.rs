@@ -7,5 +7,8 @@ pub const ERR_COMMAND_REGISTRY_NOT_FOUND: i32 = 15;
 pub const ERR_COMMAND_NOT_FOUND: i32 = 16;
 pub const ERR_COMMAND_FAILED: i32 = 17;
 pub const ERR_COMMAND_SKIPPED: i32 = 18;
+pub const ERR_COMMAND_INVALID_ARGS: i32 = 19;
+pub const ERR_COMMAND_CANCELLED: i32 = 20;
+pub const ERR_COMMAND_TIMEOUT: i32 = 21;
 
 pub const ERR_UNKNOWN_RUNTIME_ERROR: i32 = 99;
\ No newline at end of file
@@ -0,0 +1,43 @@
+use std::{env, process, sync::OnceLock};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::model::command_lock::LockOwner;
+
+/// The default duration of a lock lease, in seconds.
+const DEFAULT_LEASE_SECS: i64 = 60;
+
+/// The start date of the current process.
+static PROCESS_STARTED_AT: OnceLock<DateTime<Utc>> = OnceLock::new();
+
+/// Records the start date of the current process, to be called as early as possible.
+pub fn init() {
+    PROCESS_STARTED_AT.get_or_init(Utc::now);
+}
+
+/// Returns the current process, as a lock owner.
+pub fn current_owner() -> LockOwner {
+    LockOwner {
+        hostname: gethostname::gethostname().to_string_lossy().to_string(),
+        pid: process::id(),
+        started_at: *PROCESS_STARTED_AT.get_or_init(Utc::now),
+    }
+}
+
+/// Returns the duration of a lock lease, set by `HERMES_LOCK_LEASE_SECS`.
+///
+/// Running commands renew their lease three times per duration, so a lock is taken over at most one lease duration after its owner died.
+pub fn lease_duration() -> Duration {
+    let seconds = env::var("HERMES_LOCK_LEASE_SECS")
+        .ok()
+        .and_then(|seconds| seconds.parse::<i64>().ok())
+        .filter(|seconds| *seconds > 0)
+        .unwrap_or(DEFAULT_LEASE_SECS);
+
+    Duration::seconds(seconds)
+}
+
+/// Returns the delay between two renewals of a lock lease.
+pub fn heartbeat_interval() -> Duration {
+    lease_duration() / 3
+}
@@ -4,4 +4,11 @@ pub mod database;
 pub mod rocket_factory;
 pub mod commands;
 pub mod macros;
-pub mod exit_codes;
\ No newline at end of file
+pub mod exit_codes;
+pub mod sites_root;
+pub mod sites_watcher;
+pub mod security;
+pub mod repl;
+pub mod scheduler;
+pub mod lease;
+pub mod retention;
\ No newline at end of file
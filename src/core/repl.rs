@@ -0,0 +1,183 @@
+use std::{env, path::PathBuf};
+
+use anyhow::Result;
+use rocket::{Build, Rocket};
+use rustyline::{
+    completion::{Completer, Pair},
+    error::ReadlineError,
+    highlight::Highlighter,
+    hint::Hinter,
+    history::DefaultHistory,
+    validate::Validator,
+    Context, Editor, Helper,
+};
+
+use super::bootstrap::PreRuntimeErrors;
+use super::cli;
+use super::commands::{command_args::CommandArgs, command_registry::CommandRegistry, command_trait::CommandError, command_utils::ConsoleIO};
+
+/// The prompt of the interactive shell.
+const PROMPT: &str = "hermes> ";
+
+/// The name of the history file, in the user home directory.
+const HISTORY_FILE: &str = ".hermes_history";
+
+/// The words ending the interactive shell.
+const EXIT_WORDS: [&str; 2] = ["exit", "quit"];
+
+/// Completes command names, then the argument keys of the command being typed.
+struct ReplHelper {
+    /// The command names, with the keys of their arguments.
+    commands: Vec<(String, Vec<String>)>,
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map(|index| index + 1).unwrap_or(0);
+        let word = &line[start..];
+
+        // the first word is the command name.
+        let candidates = match line[..start].split_whitespace().next() {
+            None => self.commands
+                .iter()
+                .map(|(name, _)| name.clone())
+                .chain(EXIT_WORDS.iter().map(|word| word.to_string()))
+                .filter(|name| name.starts_with(word))
+                .collect::<Vec<String>>(),
+            Some(command_name) => self.commands
+                .iter()
+                .find(|(name, _)| name == command_name)
+                .map(|(_, keys)| keys.iter().map(|key| format!("--{}", key)).filter(|key| key.starts_with(word)).collect())
+                .unwrap_or_default(),
+        };
+
+        let pairs = candidates
+            .into_iter()
+            .map(|candidate| Pair { display: candidate.clone(), replacement: format!("{} ", candidate) })
+            .collect();
+
+        Ok((start, pairs))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+/// Returns the path of the history file, if the user home directory is known.
+fn history_path() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(|home| PathBuf::from(home).join(HISTORY_FILE))
+}
+
+/// Adds a line to the history, saved at once so it survives a crash or a killed shell.
+fn add_history(editor: &mut Editor<ReplHelper, DefaultHistory>, history_path: Option<&PathBuf>, io: &ConsoleIO, line: &str) -> Result<()> {
+    editor.add_history_entry(line)?;
+
+    if let Some(history_path) = history_path {
+        if let Err(error) = editor.save_history(history_path) {
+            io.warning(&format!("Failed to save the history: {}", error));
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the interactive shell until the user exits it.
+///
+/// The rocket instance, and thus the database connection, is shared by all the commands run from the shell.
+pub async fn run(rocket: &Rocket<Build>) -> Result<()> {
+    let io = rocket.state::<ConsoleIO>().unwrap();
+    let command_registry = rocket.state::<CommandRegistry>().unwrap();
+
+    let helper = ReplHelper {
+        commands: command_registry
+            .all()
+            .iter()
+            .map(|command| (
+                command.name().to_string(),
+                command.arguments().iter().map(|argument| argument.name.to_string()).collect(),
+            ))
+            .collect(),
+    };
+
+    let mut editor = Editor::<ReplHelper, DefaultHistory>::new()?;
+    editor.set_helper(Some(helper));
+
+    let history_path = history_path();
+
+    if let Some(history_path) = &history_path {
+        // the history file does not exist on first use.
+        let _ = editor.load_history(history_path);
+    }
+
+    io.info("Hermes interactive console, type `list` to see the commands and `exit` to quit.");
+
+    loop {
+        // reading a line blocks the current thread.
+        let line = tokio::task::block_in_place(|| editor.readline(PROMPT));
+
+        let line = match line {
+            Ok(line) => line,
+            // Ctrl-C clears the current line.
+            Err(ReadlineError::Interrupted) => continue,
+            // Ctrl-D ends the shell.
+            Err(ReadlineError::Eof) => break,
+            Err(error) => return Err(error.into()),
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Some(tokens) = shlex::split(&line) else {
+            add_history(&mut editor, history_path.as_ref(), io, &line)?;
+            io.error("Invalid input: unbalanced quotes.");
+            continue;
+        };
+
+        // the line may only contain a comment.
+        let Some(command_name) = tokens.first().map(|token| token.as_str()) else {
+            continue;
+        };
+
+        let command = command_registry.get(command_name);
+        let raw_args = cli::normalize_args(tokens[1..].to_vec());
+
+        // lines giving a secret, e.g. a password, are never written to the history.
+        if !command.is_some_and(|command| CommandArgs::contains_secret(&command.arguments(), &raw_args)) {
+            add_history(&mut editor, history_path.as_ref(), io, &line)?;
+        }
+
+        if EXIT_WORDS.contains(&command_name) {
+            break;
+        }
+
+        let Some(command) = command else {
+            let suggestions = command_registry.suggest(command_name).iter().map(|name| name.to_string()).collect();
+            io.error(&PreRuntimeErrors::FailedToGetCommand(command_name.to_string(), suggestions).to_string());
+            continue;
+        };
+
+        // errors of the command logic, cancellations and timeouts are already displayed by the command.
+        if let Err(error) = command.run(rocket, raw_args).await {
+            let command_error = error.root_cause().downcast_ref::<CommandError>();
+
+            if command_error.is_some() && !matches!(command_error, Some(CommandError::Cancelled(_) | CommandError::TimedOut(_, _))) {
+                io.error(&error.to_string());
+            }
+        }
+    }
+
+    Ok(())
+}
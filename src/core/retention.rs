@@ -0,0 +1,156 @@
+use std::{env, fmt::Display};
+
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use surrealdb::{Surreal, engine::any::Any};
+
+use crate::{
+    commands::logs_purge_command::LogsPurgeCommand,
+    middlewares::command_middleware::{CommandMiddleware, CommandMiddlewareError},
+};
+
+use super::commands::{command_args::{CommandArgs, RawCommandArgs}, command_trait::{CommandResult, CommandTrait}, command_utils::ConsoleIO};
+
+/// The delay between two automatic purges.
+const PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// The retention policy of the command logs, e.g. which closed logs are purged.
+///
+/// Running logs are never purged. Failed logs (ERROR, TIMEOUT and ABANDONED) follow their own max age when one is given, and are then neither purged nor counted by the other rules.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// The number of closed logs kept per command, the most recent ones.
+    pub keep_last: Option<u32>,
+    /// The max age of the logs.
+    pub max_age: Option<Duration>,
+    /// The max age of the failed logs, usually longer to investigate them.
+    pub failure_max_age: Option<Duration>,
+}
+
+impl RetentionPolicy {
+    /// Returns the automatic retention policy, set by `HERMES_LOG_RETENTION_KEEP_LAST`, `HERMES_LOG_RETENTION_DAYS` and `HERMES_LOG_RETENTION_ERROR_DAYS`.
+    ///
+    /// The automatic retention is disabled when none of them is set.
+    pub fn from_env() -> Option<Self> {
+        let positive = |name: &str| env::var(name)
+            .ok()
+            .and_then(|value| value.parse::<i64>().ok())
+            .filter(|value| *value > 0);
+
+        let policy = Self {
+            keep_last: positive("HERMES_LOG_RETENTION_KEEP_LAST").map(|keep_last| keep_last as u32),
+            max_age: positive("HERMES_LOG_RETENTION_DAYS").map(Duration::days),
+            failure_max_age: positive("HERMES_LOG_RETENTION_ERROR_DAYS").map(Duration::days),
+        };
+
+        match policy.is_empty() {
+            true => None,
+            false => Some(policy),
+        }
+    }
+
+    /// Either the policy purges nothing or not.
+    pub fn is_empty(&self) -> bool {
+        self.keep_last.is_none() && self.max_age.is_none() && self.failure_max_age.is_none()
+    }
+}
+
+impl Display for RetentionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut rules = Vec::new();
+
+        if let Some(keep_last) = self.keep_last {
+            rules.push(format!("keep last {} per command", keep_last));
+        }
+
+        if let Some(max_age) = self.max_age {
+            rules.push(format!("older than {} days", max_age.num_days()));
+        }
+
+        if let Some(failure_max_age) = self.failure_max_age {
+            rules.push(format!("failures older than {} days", failure_max_age.num_days()));
+        }
+
+        write!(f, "{}", rules.join(", "))
+    }
+}
+
+/// Applies the automatic retention policy in the background of the server, on startup then periodically.
+///
+/// Each purge shares the lock of the `logs:purge` command and is recorded as a `logs:purge` command log.
+pub struct LogsRetention {
+    policy: RetentionPolicy,
+    db: Surreal<Any>,
+    io: ConsoleIO,
+}
+
+impl LogsRetention {
+    /// Creates a new LogsRetention.
+    pub fn new(policy: RetentionPolicy, db: Surreal<Any>) -> Self {
+        Self {
+            policy,
+            db,
+            io: ConsoleIO::new(),
+        }
+    }
+
+    /// Purges the logs until the server stops.
+    pub async fn run(self) -> Result<()> {
+        self.io.info(&format!("Command logs retention: {}.", self.policy));
+
+        loop {
+            if let Err(error) = self.try_purge().await {
+                self.io.error(&format!("Failed to purge the command logs: {}", error));
+            }
+
+            tokio::time::sleep(PURGE_INTERVAL).await;
+        }
+    }
+
+    /// Purges the logs under the `logs:purge` lock, skipping the purge if the lock is already taken.
+    async fn try_purge(&self) -> Result<()> {
+        let command = LogsPurgeCommand;
+        let command_middleware = CommandMiddleware::new(self.db.clone());
+        let args = CommandArgs::parse(&command.arguments(), &RawCommandArgs::new())?;
+        let args_as_str = command.get_args_as_str(&args);
+
+        // a manual purge is running.
+        let log_id = match command_middleware.acquire_locks(command.name(), &args_as_str, &command.concurrency()).await {
+            Ok(log_id) => log_id,
+            Err(error) => {
+                let inner_error = error.root_cause().downcast_ref::<CommandMiddlewareError>();
+
+                if matches!(inner_error, Some(CommandMiddlewareError::AlreadyRunning(_, _) | CommandMiddlewareError::LockUnavailable(_, _))) {
+                    return Ok(());
+                }
+
+                return Err(error);
+            }
+        };
+
+        let log = command_middleware.create_log(command.name(), &args_as_str, Some(log_id)).await?;
+
+        let purged = match command_middleware.find_purgeable(&self.policy, Utc::now()).await {
+            Ok(purged) => command_middleware.delete_logs(&purged).await.map(|_| purged),
+            Err(error) => Err(error),
+        };
+
+        let (command_result, message) = match &purged {
+            Ok(purged) => (CommandResult::SUCCESS, format!("automatic purge: {} log(s) purged.", purged.len())),
+            Err(error) => (CommandResult::ERROR, format!("automatic purge: {}", error)),
+        };
+
+        let updated = command_middleware.update_log(&log, command_result, Some(message.clone())).await;
+
+        // release the lock whatever happened to the log, then report the errors.
+        command_middleware.release_lock(&log).await?;
+        updated?;
+
+        if !purged?.is_empty() {
+            self.io.info(&format!("Command logs purged ({})", message));
+        }
+
+        Ok(())
+    }
+}
+
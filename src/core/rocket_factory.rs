@@ -1,30 +1,115 @@
-use rocket::{Rocket, Build};
+use rocket::{Rocket, Build, routes};
 use anyhow::Result;
 
 
-use crate::commands::test_command::TestCommand;
+use crate::{
+    commands::{
+        test_command::TestCommand,
+        list_command::ListCommand,
+        help_command::HelpCommand,
+        sites_refresh_command::SitesRefreshCommand,
+        sites_visibility_command::SitesVisibilityCommand,
+        api_users_create_command::ApiUsersCreateCommand,
+        api_users_list_command::ApiUsersListCommand,
+        api_users_revoke_command::ApiUsersRevokeCommand,
+        users_create_command::UsersCreateCommand,
+        users_list_command::UsersListCommand,
+        users_passwd_command::UsersPasswdCommand,
+        users_status_command::UsersStatusCommand,
+        users_delete_command::UsersDeleteCommand,
+        access_grant_command::AccessGrantCommand,
+        access_revoke_command::AccessRevokeCommand,
+        access_list_command::AccessListCommand,
+        schedule_list_command::ScheduleListCommand,
+        schedule_add_command::ScheduleAddCommand,
+        schedule_remove_command::ScheduleRemoveCommand,
+        locks_list_command::LocksListCommand,
+        locks_release_command::LocksReleaseCommand,
+        logs_show_command::LogsShowCommand,
+        logs_status_command::LogsStatusCommand,
+        logs_purge_command::LogsPurgeCommand,
+        logs_stats_command::LogsStatsCommand,
+    },
+    routes::{api, auth, pages::RESERVED_PATH, static_site},
+};
 
-use super::{database::DatabaseState, commands::{command_utils::ConsoleIO, command_registry::CommandRegistry}};
+use super::{database::{Connected, DatabaseState}, sites_root::SitesRoot, commands::{command_utils::ConsoleIO, command_registry::CommandRegistry}};
 
 /// Build a rocket instance.
 /// 
 /// This function will build a rocket instance with all the required states, middlewares and routes.
 pub async fn build() -> Result<Rocket<Build>> {
+    let database = DatabaseState::connect().await?;
+
+    build_with(database)
+}
+
+/// Build a rocket instance on an existing database connection.
+///
+/// This is used to share the connection of the server with the instances running commands in its background.
+pub fn build_with(database: DatabaseState<Connected>) -> Result<Rocket<Build>> {
     // build rocket instance
     let mut build = rocket::build();
 
     // states
-    let database = DatabaseState::connect().await?;
+    let sites_root = SitesRoot::from_env()?;
     let console_io = ConsoleIO::new();
     let mut command_registry = CommandRegistry::new();
 
     // register commands
     command_registry.register(Box::new(TestCommand));
+    command_registry.register(Box::new(ListCommand));
+    command_registry.register(Box::new(HelpCommand));
+    command_registry.register(Box::new(SitesRefreshCommand));
+    command_registry.register(Box::new(SitesVisibilityCommand));
+    command_registry.register(Box::new(ApiUsersCreateCommand));
+    command_registry.register(Box::new(ApiUsersListCommand));
+    command_registry.register(Box::new(ApiUsersRevokeCommand));
+    command_registry.register(Box::new(UsersCreateCommand));
+    command_registry.register(Box::new(UsersListCommand));
+    command_registry.register(Box::new(UsersPasswdCommand));
+    command_registry.register(Box::new(UsersStatusCommand::enable()));
+    command_registry.register(Box::new(UsersStatusCommand::disable()));
+    command_registry.register(Box::new(UsersDeleteCommand));
+    command_registry.register(Box::new(AccessGrantCommand));
+    command_registry.register(Box::new(AccessRevokeCommand));
+    command_registry.register(Box::new(AccessListCommand));
+    command_registry.register(Box::new(ScheduleListCommand));
+    command_registry.register(Box::new(ScheduleAddCommand));
+    command_registry.register(Box::new(ScheduleRemoveCommand));
+    command_registry.register(Box::new(LocksListCommand));
+    command_registry.register(Box::new(LocksReleaseCommand));
+    command_registry.register(Box::new(LogsShowCommand));
+    command_registry.register(Box::new(LogsStatusCommand));
+    command_registry.register(Box::new(LogsPurgeCommand));
+    command_registry.register(Box::new(LogsStatsCommand));
 
     // manage states
     build = build.manage(database);
     build = build.manage(console_io);
     build = build.manage(command_registry);
+    build = build.manage(sites_root);
+
+    // routes
+    build = build.mount("/", routes![static_site::serve]);
+    build = build.mount(RESERVED_PATH, routes![auth::login_form, auth::login, auth::logout]);
+    build = build.mount("/api/v1", routes![
+        api::ping::ping,
+        api::users::list,
+        api::users::get,
+        api::users::create,
+        api::users::update,
+        api::users::delete,
+        api::sites::list,
+        api::sites::get,
+        api::sites::update,
+        api::site_accesses::list,
+        api::site_accesses::grant,
+        api::site_accesses::revoke,
+    ]);
+
+    // catchers
+    build = build.register("/api", api::catchers());
 
     Ok(build)
 }
\ No newline at end of file
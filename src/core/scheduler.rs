@@ -0,0 +1,203 @@
+use std::{str::FromStr, sync::Arc};
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use cron::Schedule as CronSchedule;
+use rocket::{Build, Rocket};
+use thiserror::Error;
+
+use crate::{
+    middlewares::schedule_middleware::ScheduleMiddleware,
+    model::schedule::Schedule,
+};
+
+use super::cli;
+use super::commands::{command_registry::CommandRegistry, command_trait::CommandError, command_utils::ConsoleIO};
+use super::database::{Connected, DatabaseState};
+
+/// The maximal delay between two checks of the schedules, so new schedules are picked up.
+const MAX_SLEEP_SECS: i64 = 60;
+
+/// How far back missed occurrences are looked for, e.g. after a server restart.
+const MAX_CATCH_UP_HOURS: i64 = 24;
+
+#[derive(Debug, Error)]
+pub enum SchedulerError {
+    #[error("invalid cron expression {0}: {1}")]
+    InvalidCron(String, String),
+}
+
+/// Parses a cron expression.
+///
+/// Both standard 5 fields expressions (`min hour day month weekday`) and 6 or 7 fields expressions, starting with seconds and optionally ending with years, are accepted.
+pub fn parse_cron(expression: &str) -> Result<CronSchedule, SchedulerError> {
+    let expression = expression.trim();
+
+    let expression = match expression.split_whitespace().count() {
+        5 => format!("0 {}", expression),
+        _ => expression.to_string(),
+    };
+
+    CronSchedule::from_str(&expression).map_err(|error| SchedulerError::InvalidCron(expression, error.to_string()))
+}
+
+/// Computes the next run of a schedule after a date.
+pub fn next_run(schedule: &Schedule, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let since = schedule.last_tick_at.map(|last_tick_at| last_tick_at.max(after)).unwrap_or(after);
+
+    parse_cron(&schedule.cron).ok()?.after(&since).next()
+}
+
+/// Computes the occurrence of a schedule to run now, if any.
+///
+/// Only the latest due occurrence is run, missed ones are not caught up, and occurrences older than the catch-up window (e.g. while the server was down) are ignored.
+pub fn due_occurrence(cron: &CronSchedule, schedule: &Schedule, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let since = schedule.last_tick_at
+        .unwrap_or(schedule.created_at)
+        .max(now - Duration::hours(MAX_CATCH_UP_HOURS));
+
+    cron.after(&since).take_while(|occurrence| *occurrence <= now).last()
+}
+
+/// Runs the scheduled commands in the background of the server.
+///
+/// Each occurrence of a schedule is claimed in database before running, so several servers sharing the database never run it twice. The commands then go through their usual lifecycle, including locks and command logs.
+pub struct Scheduler {
+    /// The rocket instance used to run the commands, distinct from the one serving HTTP requests.
+    rocket: Arc<Rocket<Build>>,
+    /// The output of the scheduler.
+    io: ConsoleIO,
+}
+
+impl Scheduler {
+    /// Creates a new Scheduler.
+    pub fn new(rocket: Rocket<Build>) -> Self {
+        Self {
+            rocket: Arc::new(rocket),
+            io: ConsoleIO::new(),
+        }
+    }
+
+    /// Checks the schedules until the server stops.
+    pub async fn run(&self) -> Result<()> {
+        let db_conn = self.rocket.state::<DatabaseState<Connected>>().unwrap();
+        let schedule_middleware = ScheduleMiddleware::new(db_conn.get_new_connection());
+
+        loop {
+            let now = Utc::now();
+            let mut wake_at = now + Duration::seconds(MAX_SLEEP_SECS);
+
+            match schedule_middleware.find_all().await {
+                Ok(schedules) => {
+                    for schedule in schedules.iter().filter(|schedule| schedule.enabled) {
+                        if let Some(next_run_at) = self.tick(&schedule_middleware, schedule, now).await {
+                            wake_at = wake_at.min(next_run_at);
+                        }
+                    }
+                }
+                Err(error) => self.io.error(&format!("Failed to load the schedules: {}", error)),
+            }
+
+            let sleep = (wake_at - Utc::now()).to_std().unwrap_or_default();
+            tokio::time::sleep(sleep).await;
+        }
+    }
+
+    /// Runs a schedule if an occurrence is due, returning its next run.
+    async fn tick(&self, schedule_middleware: &ScheduleMiddleware, schedule: &Schedule, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let cron = match parse_cron(&schedule.cron) {
+            Ok(cron) => cron,
+            Err(error) => {
+                self.io.error(&format!("Schedule of {} skipped: {}", schedule.command_name, error));
+                return None;
+            }
+        };
+
+        if let Some(tick) = due_occurrence(&cron, schedule, now) {
+            match schedule_middleware.claim(schedule, tick).await {
+                Ok(true) => self.spawn(schedule),
+                Ok(false) => {}
+                Err(error) => self.io.error(&format!("Failed to claim the schedule of {}: {}", schedule.command_name, error)),
+            }
+        }
+
+        cron.after(&now).next()
+    }
+
+    /// Runs the command of a schedule in a background task.
+    fn spawn(&self, schedule: &Schedule) {
+        let rocket = self.rocket.clone();
+        let command_name = schedule.command_name.clone();
+        let command_args = cli::normalize_args(schedule.command_args.clone());
+
+        tokio::spawn(async move {
+            let io = rocket.state::<ConsoleIO>().unwrap();
+            let command_registry = rocket.state::<CommandRegistry>().unwrap();
+
+            let Some(command) = command_registry.get(&command_name) else {
+                io.error(&format!("Scheduled command {} not found.", command_name));
+                return;
+            };
+
+            // errors of the command logic are already displayed by the command.
+            if let Err(error) = command.run(&rocket, command_args).await {
+                if let Some(command_error) = error.root_cause().downcast_ref::<CommandError>() {
+                    io.warning(&format!("Scheduled command not completed: {}", command_error));
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    /// A schedule created at midnight, January 1st.
+    fn schedule(cron: &str, last_tick_at: Option<DateTime<Utc>>) -> Schedule {
+        Schedule {
+            id: None,
+            command_name: "test:scheduled".to_string(),
+            command_args: Vec::new(),
+            cron: cron.to_string(),
+            enabled: true,
+            created_at: at(1, 0, 0),
+            last_tick_at,
+        }
+    }
+
+    /// A date of January.
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn parses_standard_and_extended_cron_expressions() {
+        let standard = parse_cron("30 2 * * *").unwrap();
+        let extended = parse_cron("0 30 2 * * *").unwrap();
+
+        assert_eq!(standard.after(&at(1, 0, 0)).next(), Some(at(1, 2, 30)));
+        assert_eq!(standard.after(&at(1, 0, 0)).next(), extended.after(&at(1, 0, 0)).next());
+        assert!(matches!(parse_cron("every day"), Err(SchedulerError::InvalidCron(_, _))));
+    }
+
+    #[test]
+    fn runs_the_latest_due_occurrence_only() {
+        let cron = parse_cron("0 * * * *").unwrap();
+
+        assert_eq!(due_occurrence(&cron, &schedule("0 * * * *", Some(at(1, 1, 0))), at(1, 4, 30)), Some(at(1, 4, 0)));
+        assert_eq!(due_occurrence(&cron, &schedule("0 * * * *", Some(at(1, 4, 0))), at(1, 4, 30)), None);
+    }
+
+    #[test]
+    fn ignores_the_occurrences_older_than_the_catch_up_window() {
+        let cron = parse_cron("0 12 1 * *").unwrap();
+
+        assert_eq!(due_occurrence(&cron, &schedule("0 12 1 * *", None), at(1, 13, 0)), Some(at(1, 12, 0)));
+
+        // the occurrence was missed more than a day ago, e.g. while the server was down.
+        assert_eq!(due_occurrence(&cron, &schedule("0 12 1 * *", None), at(2, 13, 0)), None);
+    }
+}
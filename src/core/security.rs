@@ -0,0 +1,48 @@
+use anyhow::{Result, anyhow};
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use rand::{distributions::Alphanumeric, rngs::OsRng, Rng};
+
+/// A valid argon2id hash, verified when no record matches an identifier (API key prefix, login...).
+///
+/// This way, unknown identifiers take as long to be refused as wrong secrets.
+pub const DUMMY_SECRET_HASH: &str = "$argon2id$v=19$m=19456,t=2,p=1$HHqnWX3pW4i4JPcLGJgQ8g$TWRP5ct4S9FNxvHPqI6v8Kffmu+WLoyBkkSbRu+KNto";
+
+/// Hashes a secret (password, API key...) using argon2id with a random salt.
+///
+/// The result is a PHC string embedding the algorithm parameters and the salt.
+pub fn hash_secret(secret: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+
+    let hash = Argon2::default()
+        .hash_password(secret.as_bytes(), &salt)
+        .map_err(|error| anyhow!("failed to hash secret: {}", error))?;
+
+    Ok(hash.to_string())
+}
+
+/// Verifies a secret against an argon2id PHC string.
+///
+/// The hash comparison is done in constant time.
+pub fn verify_secret(secret: &str, hash: &str) -> bool {
+    let possible_hash = PasswordHash::new(hash);
+
+    if possible_hash.is_err() {
+        return false;
+    }
+
+    Argon2::default()
+        .verify_password(secret.as_bytes(), &possible_hash.unwrap())
+        .is_ok()
+}
+
+/// Generates a random alphanumeric token using the OS random generator.
+pub fn generate_token(length: usize) -> String {
+    OsRng
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
@@ -0,0 +1,281 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use thiserror::Error;
+
+/// Default location of the sites root if `HERMES_SITES_ROOT` is not set.
+const DEFAULT_SITES_ROOT: &str = "./sites";
+
+/// Default domain the sites are served under if `HERMES_BASE_DOMAIN` is not set.
+const DEFAULT_BASE_DOMAIN: &str = "localhost";
+
+/// File served when a directory is requested.
+pub const INDEX_FILE: &str = "index.html";
+
+#[derive(Debug, Error)]
+pub enum SitesRootError {
+    #[error("invalid path: {0}")]
+    InvalidPath(String),
+    #[error("path {0} escapes the site directory.")]
+    Forbidden(String),
+    #[error("{0} not found.")]
+    NotFound(String),
+    #[error("filesystem error: {0}")]
+    FilesystemError(String),
+}
+
+/// Checks if a label can be used as a site subdomain (ASCII alphanumeric characters and `-`).
+pub fn is_valid_subdomain(label: &str) -> bool {
+    !label.is_empty()
+        && !label.starts_with('-')
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// A struct representing the directory containing all the static sites.
+///
+/// Each sub-directory of the sites root is a site, served on the subdomain of the same name under the base domain.
+#[derive(Debug, Clone)]
+pub struct SitesRoot {
+    path: PathBuf,
+    base_domain: String,
+}
+
+impl SitesRoot {
+    /// Creates a SitesRoot from the `HERMES_SITES_ROOT` and `HERMES_BASE_DOMAIN` environment variables.
+    ///
+    /// Defaults to `./sites` and `localhost` if the variables are not set.
+    pub fn from_env() -> Result<Self> {
+        let path = env::var("HERMES_SITES_ROOT").unwrap_or(DEFAULT_SITES_ROOT.to_string());
+        let base_domain = env::var("HERMES_BASE_DOMAIN").unwrap_or(DEFAULT_BASE_DOMAIN.to_string());
+
+        Ok(Self::new(PathBuf::from(path), &base_domain))
+    }
+
+    /// Creates a SitesRoot from a path and the domain the sites are served under.
+    pub fn new(path: PathBuf, base_domain: &str) -> Self {
+        Self {
+            path,
+            base_domain: base_domain.trim_matches('.').to_ascii_lowercase(),
+        }
+    }
+
+    /// Returns the path of the sites root, as configured.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the lowercased domain the sites are served under, e.g. `example.com` for `docs.example.com`.
+    pub fn base_domain(&self) -> &str {
+        &self.base_domain
+    }
+
+    /// Lists the site directories of the sites root.
+    ///
+    /// Hidden directories and directories whose name is not a valid subdomain are ignored.
+    pub async fn scan(&self) -> Result<Vec<String>, SitesRootError> {
+        let mut entries = tokio::fs::read_dir(&self.path)
+            .await
+            .map_err(|error| SitesRootError::FilesystemError(error.to_string()))?;
+
+        let mut directories = Vec::<String>::new();
+
+        while let Some(entry) = entries
+            .next_entry()
+            .await
+            .map_err(|error| SitesRootError::FilesystemError(error.to_string()))?
+        {
+            // follows symlinks, so linked site directories are listed too.
+            let is_dir = tokio::fs::metadata(entry.path())
+                .await
+                .map(|metadata| metadata.is_dir())
+                .unwrap_or(false);
+
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if is_dir && is_valid_subdomain(&name) {
+                directories.push(name);
+            }
+        }
+
+        directories.sort();
+
+        Ok(directories)
+    }
+
+    /// Resolves a requested file of a site into a canonical path on the filesystem.
+    ///
+    /// `site_dir` is the name of the site directory under the sites root, `segments` are the percent-decoded segments of the requested path.
+    ///
+    /// This will refuse :
+    /// - segments that are `.` or `..`, hidden files or segments containing separators (e.g. encoded `%2F`),
+    /// - any path, including symlinks, that resolves outside of the site directory.
+    ///
+    /// If the requested path is a directory, its `index.html` file is resolved instead.
+    pub async fn resolve_file(&self, site_dir: &str, segments: &[&str]) -> Result<PathBuf, SitesRootError> {
+        // check the site directory and the requested segments.
+        Self::check_segment(site_dir)?;
+
+        for segment in segments.iter() {
+            Self::check_segment(segment)?;
+        }
+
+        // canonicalize the site directory, which must be inside the sites root.
+        let root = Self::canonicalize(&self.path).await?;
+        let site_root = Self::canonicalize(&root.join(site_dir)).await?;
+
+        if !site_root.starts_with(&root) || !site_root.is_dir() {
+            return Err(SitesRootError::Forbidden(site_dir.to_string()));
+        }
+
+        // canonicalize the requested file, resolving symlinks.
+        let mut requested = site_root.clone();
+        requested.extend(segments.iter());
+
+        let mut resolved = Self::canonicalize(&requested).await?;
+
+        // serve the index file of directories.
+        if resolved.is_dir() {
+            resolved = Self::canonicalize(&resolved.join(INDEX_FILE)).await?;
+        }
+
+        if !resolved.starts_with(&site_root) {
+            return Err(SitesRootError::Forbidden(segments.join("/")));
+        }
+
+        if !resolved.is_file() {
+            return Err(SitesRootError::NotFound(segments.join("/")));
+        }
+
+        Ok(resolved)
+    }
+
+    /// Checks that a path segment cannot be used to traverse the filesystem.
+    fn check_segment(segment: &str) -> Result<(), SitesRootError> {
+        let is_invalid = segment.is_empty()
+            || segment == "."
+            || segment == ".."
+            || segment.contains(['/', '\\', '\0']);
+
+        if is_invalid {
+            return Err(SitesRootError::InvalidPath(segment.to_string()));
+        }
+
+        // hidden files (e.g. .git, .env) are never served.
+        if segment.starts_with('.') {
+            return Err(SitesRootError::NotFound(segment.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Canonicalizes a path, mapping a missing file to a NotFound error.
+    async fn canonicalize(path: &Path) -> Result<PathBuf, SitesRootError> {
+        match tokio::fs::canonicalize(path).await {
+            Ok(canonical) => Ok(canonical),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                Err(SitesRootError::NotFound(path.display().to_string()))
+            }
+            Err(error) => Err(SitesRootError::FilesystemError(error.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::process;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// The number of temporary directories created by the tests, to name them uniquely.
+    static TEMP_DIRS: AtomicUsize = AtomicUsize::new(0);
+
+    /// A temporary directory, removed when dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let path = env::temp_dir().join(format!("hermes-sites-root-{}-{}", process::id(), TEMP_DIRS.fetch_add(1, Ordering::SeqCst)));
+            fs::create_dir_all(&path).unwrap();
+
+            Self(path)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Creates a sites root with a `docs` site, and a secret file next to it.
+    fn sites_root() -> (TempDir, SitesRoot) {
+        let dir = TempDir::new();
+
+        fs::create_dir_all(dir.0.join("sites/docs/guide")).unwrap();
+        fs::write(dir.0.join("sites/docs/index.html"), "docs").unwrap();
+        fs::write(dir.0.join("sites/docs/guide/index.html"), "guide").unwrap();
+        fs::write(dir.0.join("sites/docs/.env"), "hidden").unwrap();
+        fs::write(dir.0.join("secret.txt"), "secret").unwrap();
+
+        let sites_root = SitesRoot::new(dir.0.join("sites"), "example.com");
+
+        (dir, sites_root)
+    }
+
+    #[tokio::test]
+    async fn resolves_files_and_directory_indexes() {
+        let (_dir, sites_root) = sites_root();
+
+        let index = sites_root.resolve_file("docs", &[]).await.unwrap();
+        let guide = sites_root.resolve_file("docs", &["guide"]).await.unwrap();
+
+        assert!(index.ends_with("docs/index.html"));
+        assert!(guide.ends_with("docs/guide/index.html"));
+    }
+
+    #[tokio::test]
+    async fn refuses_dot_segments() {
+        let (_dir, sites_root) = sites_root();
+
+        for segments in [vec![".."], vec!["."], vec!["guide", "..", ".."], vec![""]] {
+            let resolved = sites_root.resolve_file("docs", &segments).await;
+
+            assert!(matches!(resolved, Err(SitesRootError::InvalidPath(_))), "{:?} was resolved", segments);
+        }
+
+        assert!(matches!(sites_root.resolve_file("..", &["secret.txt"]).await, Err(SitesRootError::InvalidPath(_))));
+    }
+
+    #[tokio::test]
+    async fn refuses_hidden_segments() {
+        let (_dir, sites_root) = sites_root();
+
+        assert!(matches!(sites_root.resolve_file("docs", &[".env"]).await, Err(SitesRootError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn refuses_nul_and_encoded_separators() {
+        let (_dir, sites_root) = sites_root();
+
+        for segment in ["index.html\0", "../secret.txt", "..\\secret.txt", "guide/index.html"] {
+            let resolved = sites_root.resolve_file("docs", &[segment]).await;
+
+            assert!(matches!(resolved, Err(SitesRootError::InvalidPath(_))), "{:?} was resolved", segment);
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn refuses_symlinks_outside_of_the_site() {
+        let (dir, sites_root) = sites_root();
+
+        std::os::unix::fs::symlink(dir.0.join("secret.txt"), dir.0.join("sites/docs/secret.txt")).unwrap();
+        std::os::unix::fs::symlink(&dir.0, dir.0.join("sites/docs/parent")).unwrap();
+
+        assert!(matches!(sites_root.resolve_file("docs", &["secret.txt"]).await, Err(SitesRootError::Forbidden(_))));
+        assert!(matches!(sites_root.resolve_file("docs", &["parent", "secret.txt"]).await, Err(SitesRootError::Forbidden(_))));
+    }
+}
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use notify_debouncer_mini::{new_debouncer, notify::RecursiveMode, DebounceEventResult};
+use surrealdb::{Surreal, engine::any::Any};
+use tokio::sync::mpsc;
+
+use crate::{
+    commands::sites_refresh_command::SitesRefreshCommand,
+    core::commands::{command_args::{CommandArgs, RawCommandArgs}, command_trait::{CommandResult, CommandTrait}, command_utils::ConsoleIO},
+    middlewares::{
+        command_middleware::{CommandMiddleware, CommandMiddlewareError},
+        site_middleware::{SiteChange, SiteMiddleware},
+    },
+};
+
+use super::sites_root::SitesRoot;
+
+/// Delay used to group bursts of filesystem events (e.g. a deploy) into a single synchronization.
+const DEBOUNCE_DELAY: Duration = Duration::from_secs(2);
+
+/// Delay before retrying a synchronization that was skipped because a refresh was already running.
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// A background watcher keeping the sites in database in sync with the sites root.
+///
+/// Each synchronization shares the lock of the `sites:refresh` command and is recorded as a `sites:refresh` command log.
+pub struct SitesWatcher {
+    sites_root: SitesRoot,
+    db: Surreal<Any>,
+    io: ConsoleIO,
+}
+
+impl SitesWatcher {
+    /// Creates a new SitesWatcher.
+    pub fn new(sites_root: SitesRoot, db: Surreal<Any>) -> Self {
+        Self {
+            sites_root,
+            db,
+            io: ConsoleIO::new(),
+        }
+    }
+
+    /// Watches the top-level directories of the sites root until the program stops.
+    ///
+    /// A first synchronization is done on startup, to catch up with changes made while the server was down.
+    pub async fn watch(self) -> Result<()> {
+        let (sender, mut receiver) = mpsc::unbounded_channel::<DebounceEventResult>();
+
+        // the debouncer must live as long as the watch loop.
+        let mut debouncer = new_debouncer(DEBOUNCE_DELAY, move |result: DebounceEventResult| {
+            let _ = sender.send(result);
+        })?;
+
+        debouncer
+            .watcher()
+            .watch(self.sites_root.path(), RecursiveMode::NonRecursive)?;
+
+        let mut pending = true;
+
+        loop {
+            if pending {
+                pending = !self.sync().await;
+            }
+
+            // wait for changes, or retry a pending synchronization.
+            let delay = if pending { RETRY_DELAY } else { Duration::MAX };
+
+            match tokio::time::timeout(delay, receiver.recv()).await {
+                Ok(Some(Ok(_))) => pending = true,
+                Ok(Some(Err(error))) => self.io.error(&format!("Sites watcher error: {}", error)),
+                Ok(None) => bail!("sites watcher channel closed."),
+                Err(_) => {}
+            }
+        }
+    }
+
+    /// Synchronizes the sites, returning false if the synchronization must be retried.
+    async fn sync(&self) -> bool {
+        match self.try_sync().await {
+            Ok(done) => done,
+            Err(error) => {
+                self.io.error(&format!("Failed to synchronize sites: {}", error));
+                false
+            }
+        }
+    }
+
+    /// Synchronizes the sites under the `sites:refresh` locks, returning false if a lock is already taken.
+    async fn try_sync(&self) -> Result<bool> {
+        let command = SitesRefreshCommand;
+        let command_middleware = CommandMiddleware::new(self.db.clone());
+        let site_middleware = SiteMiddleware::new(self.db.clone());
+        let args = CommandArgs::parse(&command.arguments(), &RawCommandArgs::new())?;
+        let args_as_str = command.get_args_as_str(&args);
+
+        // a manual refresh or another command of the sites group is running, retry later.
+        let log_id = match command_middleware.acquire_locks(command.name(), &args_as_str, &command.concurrency()).await {
+            Ok(log_id) => log_id,
+            Err(error) => {
+                let inner_error = error.root_cause().downcast_ref::<CommandMiddlewareError>();
+
+                if matches!(inner_error, Some(CommandMiddlewareError::AlreadyRunning(_, _) | CommandMiddlewareError::LockUnavailable(_, _))) {
+                    return Ok(false);
+                }
+
+                return Err(error);
+            }
+        };
+
+        let log = command_middleware.create_log(command.name(), &args_as_str, Some(log_id)).await?;
+
+        let synced = match self.sites_root.scan().await {
+            Ok(directories) => site_middleware.refresh(&directories).await,
+            Err(error) => Err(error.into()),
+        };
+
+        let (command_result, message) = match &synced {
+            Ok(report) => (CommandResult::SUCCESS, format!(
+                "automatic sync: {} created, {} restored, {} missing, {} unchanged.",
+                report.count(SiteChange::CREATED),
+                report.count(SiteChange::RESTORED),
+                report.count(SiteChange::MISSING),
+                report.count(SiteChange::UNCHANGED),
+            )),
+            Err(error) => (CommandResult::ERROR, format!("automatic sync: {}", error)),
+        };
+
+        let updated = command_middleware.update_log(&log, command_result, Some(message.clone())).await;
+
+        // release the lock whatever happened to the log, then report the errors.
+        command_middleware.release_lock(&log).await?;
+        updated?;
+
+        let report = match synced {
+            Ok(report) => report,
+            Err(error) => bail!(error),
+        };
+
+        for warning in report.warnings.iter() {
+            self.io.warning(warning);
+        }
+
+        self.io.info(&format!("Sites synchronized ({})", message));
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,3 @@
+pub mod user_dto;
+pub mod site_dto;
+pub mod site_access_dto;
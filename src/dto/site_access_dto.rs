@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::model::site_access::SiteAccess;
+
+/// A site access, as exposed by the API.
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteAccessDto {
+    /// The login of the user.
+    pub login: String,
+    /// The subdomain of the site.
+    pub site: String,
+    pub granted_by: String,
+    pub granted_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub expired: bool,
+}
+
+impl SiteAccessDto {
+    /// Creates a SiteAccessDto from a site access and the login and subdomain it refers to.
+    pub fn new(site_access: SiteAccess, login: &str, site: &str) -> Self {
+        Self {
+            login: login.to_string(),
+            site: site.to_string(),
+            expired: site_access.is_expired(),
+            granted_by: site_access.granted_by,
+            granted_at: site_access.granted_at,
+            expires_at: site_access.expires_at,
+        }
+    }
+}
+
+/// The payload to grant a user access to a site.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GrantSiteAccessDto {
+    /// The login of the user.
+    pub login: String,
+    /// The subdomain of the site.
+    pub site: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::model::site::{Site, SiteVisibility};
+
+/// A site, as exposed by the API.
+#[derive(Debug, Clone, Serialize)]
+pub struct SiteDto {
+    pub name: String,
+    pub subdomain: String,
+    pub path: String,
+    pub enabled: bool,
+    pub missing: bool,
+    pub visibility: SiteVisibility,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Site> for SiteDto {
+    fn from(site: Site) -> Self {
+        Self {
+            name: site.name,
+            subdomain: site.subdomain,
+            path: site.path,
+            enabled: site.enabled,
+            missing: site.missing,
+            visibility: site.visibility,
+            created_at: site.created_at,
+            updated_at: site.updated_at,
+        }
+    }
+}
+
+/// The payload to update a site, missing fields are left unchanged.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateSiteDto {
+    pub name: Option<String>,
+    pub enabled: Option<bool>,
+    pub visibility: Option<SiteVisibility>,
+}
@@ -0,0 +1,43 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::model::user::User;
+
+/// A user, as exposed by the API (the password hash is never exposed).
+#[derive(Debug, Clone, Serialize)]
+pub struct UserDto {
+    pub login: String,
+    pub display_name: String,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+}
+
+impl From<User> for UserDto {
+    fn from(user: User) -> Self {
+        Self {
+            login: user.login,
+            display_name: user.display_name,
+            enabled: user.enabled,
+            created_at: user.created_at,
+            last_login_at: user.last_login_at,
+        }
+    }
+}
+
+/// The payload to create a user.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateUserDto {
+    pub login: String,
+    pub password: String,
+    #[serde(default)]
+    pub display_name: String,
+}
+
+/// The payload to update a user, missing fields are left unchanged.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateUserDto {
+    pub display_name: Option<String>,
+    pub password: Option<String>,
+    pub enabled: Option<bool>,
+}
@@ -0,0 +1,58 @@
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+use thiserror::Error;
+
+use crate::core::database::{Connected, DatabaseState};
+use crate::middlewares::api_user_middleware::ApiUserMiddleware;
+use crate::model::api_user::ApiUser;
+
+/// The header containing the API key.
+pub const API_KEY_HEADER: &str = "X-API-Key";
+
+#[derive(Debug, Clone, Error)]
+pub enum ApiKeyError {
+    #[error("missing {} header.", API_KEY_HEADER)]
+    Missing,
+    #[error("invalid, revoked or expired API key.")]
+    Invalid,
+    #[error("failed to authenticate API key.")]
+    DatabaseError,
+}
+
+/// A request guard authenticating the API user of the `X-API-Key` header.
+///
+/// On failure, the error is stored in the request local cache for the API catchers.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedApiUser(pub ApiUser);
+
+impl AuthenticatedApiUser {
+    /// Fails the request, keeping the error for the API catchers.
+    fn failure(request: &Request<'_>, status: Status, error: ApiKeyError) -> Outcome<Self, ApiKeyError> {
+        request.local_cache(|| Some(error.clone()));
+
+        Outcome::Failure((status, error))
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AuthenticatedApiUser {
+    type Error = ApiKeyError;
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let possible_api_key = request.headers().get_one(API_KEY_HEADER);
+
+        if possible_api_key.is_none() {
+            return Self::failure(request, Status::Unauthorized, ApiKeyError::Missing);
+        }
+
+        let db_conn = request.rocket().state::<DatabaseState<Connected>>().unwrap();
+        let api_user_middleware = ApiUserMiddleware::new(db_conn.get_new_connection());
+
+        match api_user_middleware.authenticate(possible_api_key.unwrap()).await {
+            Ok(Some(api_user)) => Outcome::Success(Self(api_user)),
+            Ok(None) => Self::failure(request, Status::Unauthorized, ApiKeyError::Invalid),
+            Err(_) => Self::failure(request, Status::InternalServerError, ApiKeyError::DatabaseError),
+        }
+    }
+}
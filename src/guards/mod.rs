@@ -0,0 +1,4 @@
+pub mod site_host;
+pub mod site;
+pub mod session;
+pub mod api_key;
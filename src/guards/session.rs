@@ -0,0 +1,102 @@
+use chrono::{DateTime, Duration, Utc};
+use rocket::http::{Cookie, CookieJar, SameSite};
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+use serde::{Deserialize, Serialize};
+
+use crate::core::database::{Connected, DatabaseState};
+use crate::middlewares::user_middleware::UserMiddleware;
+use crate::model::user::User;
+
+use super::site_host::SiteHost;
+
+/// The name of the session cookie.
+pub const SESSION_COOKIE: &str = "hermes_session";
+
+/// The lifetime of a session, in hours.
+const SESSION_DURATION_HOURS: i64 = 12;
+
+/// The content of the session cookie, encrypted by Rocket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    /// The record id of the logged in user.
+    pub user_id: String,
+    /// The subdomain the session was opened on.
+    pub subdomain: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Session {
+    /// Opens a session for a user on a subdomain.
+    pub fn new(user: &User, subdomain: &str) -> Self {
+        Self {
+            user_id: user.id.as_ref().unwrap().id.to_raw(),
+            subdomain: subdomain.to_string(),
+            expires_at: Utc::now() + Duration::hours(SESSION_DURATION_HOURS),
+        }
+    }
+
+    /// Stores the session in a private cookie.
+    ///
+    /// The cookie has no `Domain` attribute, so browsers only send it back to the exact subdomain it was set on.
+    pub fn save(&self, cookies: &CookieJar<'_>) {
+        let cookie = Cookie::build(SESSION_COOKIE, serde_json::to_string(self).unwrap())
+            .path("/")
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .max_age(rocket::time::Duration::hours(SESSION_DURATION_HOURS))
+            .finish();
+
+        cookies.add_private(cookie);
+    }
+
+    /// Removes the session cookie.
+    pub fn clear(cookies: &CookieJar<'_>) {
+        cookies.remove_private(Cookie::named(SESSION_COOKIE));
+    }
+
+    /// Reads the session of a request, if any and still valid for the subdomain.
+    fn from_cookies(cookies: &CookieJar<'_>, subdomain: &str) -> Option<Self> {
+        let cookie = cookies.get_private(SESSION_COOKIE)?;
+        let session = serde_json::from_str::<Self>(cookie.value()).ok()?;
+
+        if session.subdomain != subdomain || session.expires_at <= Utc::now() {
+            return None;
+        }
+
+        Some(session)
+    }
+}
+
+/// A request guard resolving the user logged in on the request subdomain.
+///
+/// Requests without a valid session are forwarded, use `Option<AuthenticatedUser>` to handle anonymous requests.
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser(pub User);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for AuthenticatedUser {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let site_host = match request.guard::<SiteHost>().await {
+            Outcome::Success(site_host) => site_host,
+            _ => return Outcome::Forward(()),
+        };
+
+        let possible_session = Session::from_cookies(request.cookies(), &site_host.subdomain);
+
+        if possible_session.is_none() {
+            return Outcome::Forward(());
+        }
+
+        let db_conn = request.rocket().state::<DatabaseState<Connected>>().unwrap();
+        let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+
+        // the user may have been disabled or deleted since the login.
+        match user_middleware.find_by_id(&possible_session.unwrap().user_id).await {
+            Ok(Some(user)) if user.enabled => Outcome::Success(Self(user)),
+            _ => Outcome::Forward(()),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use rocket::http::Status;
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+
+use crate::core::database::{Connected, DatabaseState};
+use crate::middlewares::site_middleware::SiteMiddleware;
+use crate::model::site::Site;
+
+use super::site_host::SiteHost;
+
+/// A request guard resolving the site matching the request subdomain.
+///
+/// Requests to unknown, disabled or missing sites are forwarded, ending up as 404 errors.
+#[derive(Debug, Clone)]
+pub struct RequestedSite(pub Site);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for RequestedSite {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let site_host = match request.guard::<SiteHost>().await {
+            Outcome::Success(site_host) => site_host,
+            _ => return Outcome::Forward(()),
+        };
+
+        let db_conn = request.rocket().state::<DatabaseState<Connected>>().unwrap();
+        let site_middleware = SiteMiddleware::new(db_conn.get_new_connection());
+
+        match site_middleware.find_by_subdomain(&site_host.subdomain).await {
+            Ok(Some(site)) if site.enabled && !site.missing => Outcome::Success(Self(site)),
+            Ok(_) => Outcome::Forward(()),
+            Err(_) => Outcome::Failure((Status::InternalServerError, ())),
+        }
+    }
+}
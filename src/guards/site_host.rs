@@ -0,0 +1,89 @@
+use std::net::IpAddr;
+
+use rocket::request::{FromRequest, Outcome};
+use rocket::Request;
+
+use crate::core::sites_root::{is_valid_subdomain, SitesRoot};
+
+/// A request guard extracting the site subdomain from the `Host` header.
+///
+/// The subdomain is the label preceding the base domain of the sites, e.g. `docs.example.com` => `docs` for `example.com`.
+///
+/// Requests without a valid subdomain (e.g. an IP address, the base domain itself or another domain) are forwarded to the next matching route.
+#[derive(Debug, Clone)]
+pub struct SiteHost {
+    /// The lowercased subdomain.
+    pub subdomain: String,
+}
+
+impl SiteHost {
+    /// Extracts the subdomain from a domain name, if it is a direct subdomain of the base domain.
+    pub fn parse(domain: &str, base_domain: &str) -> Option<Self> {
+        let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+
+        if domain.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>().is_ok() {
+            return None;
+        }
+
+        let subdomain = domain
+            .strip_suffix(base_domain)?
+            .strip_suffix('.')?;
+
+        if subdomain.contains('.') || !is_valid_subdomain(subdomain) {
+            return None;
+        }
+
+        Some(Self {
+            subdomain: subdomain.to_string(),
+        })
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for SiteHost {
+    type Error = ();
+
+    async fn from_request(request: &'r Request<'_>) -> Outcome<Self, Self::Error> {
+        let sites_root = request.rocket().state::<SitesRoot>().unwrap();
+
+        let possible_site_host = request
+            .host()
+            .and_then(|host| Self::parse(host.domain().as_str(), sites_root.base_domain()));
+
+        match possible_site_host {
+            Some(site_host) => Outcome::Success(site_host),
+            None => Outcome::Forward(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subdomain(domain: &str) -> Option<String> {
+        SiteHost::parse(domain, "example.com").map(|site_host| site_host.subdomain)
+    }
+
+    #[test]
+    fn parses_the_subdomains_of_the_base_domain() {
+        assert_eq!(subdomain("docs.example.com"), Some("docs".to_string()));
+        assert_eq!(subdomain("Docs.Example.COM."), Some("docs".to_string()));
+    }
+
+    #[test]
+    fn refuses_the_base_domain_and_other_domains() {
+        assert_eq!(subdomain("example.com"), None);
+        assert_eq!(subdomain("docs.example.org"), None);
+        assert_eq!(subdomain("docs.notexample.com"), None);
+        assert_eq!(subdomain("v2.docs.example.com"), None);
+        assert_eq!(subdomain("-docs.example.com"), None);
+    }
+
+    #[test]
+    fn refuses_ip_addresses() {
+        assert_eq!(subdomain("127.0.0.1"), None);
+        assert_eq!(SiteHost::parse("10.0.0.1", "0.0.1").map(|site_host| site_host.subdomain), None);
+        assert_eq!(subdomain("[::1]"), None);
+    }
+}
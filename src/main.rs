@@ -1,4 +1,4 @@
-use crate::core::{rocket_factory, exit_codes, commands::command_utils::ConsoleIO, bootstrap, cli, bootstrap::PreRuntimeErrors};
+use crate::core::{rocket_factory, exit_codes, lease, commands::command_utils::{self, ConsoleIO}, bootstrap, cli, bootstrap::PreRuntimeErrors};
 
 use clap::Parser;
 use anyhow::Result;
@@ -7,23 +7,42 @@ pub mod core;
 pub mod model;
 pub mod middlewares;
 pub mod commands;
+pub mod dto;
+pub mod guards;
+pub mod routes;
 
 /// main entrypoint of the program.
 #[tokio::main]
 async fn main() -> Result<()> {
 
+    // Record the process start date, identifying the locks of this process.
+    lease::init();
+
+    // Parse the command line arguments.
+    let cli = cli::Cli::parse();
+
+    // Configure the console outputs, before any output.
+    command_utils::configure_output(cli.output, cli.no_color);
+
+    // Configure the prompts, the server never asking anything.
+    let no_interaction = cli.no_interaction || matches!(cli.subcommand, cli::Command::Server);
+    let interaction = command_utils::configure_interaction(no_interaction, cli.answers.as_deref());
+
     // Create a new ConsoleIO instance.
     let io = ConsoleIO::new();
 
+    // If the answers file could not be loaded, exit the program.
+    if let Err(error) = &interaction {
+        io.error(&format!("Failed to configure the prompts: {:#}", error));
+        exit!(exit_codes::ERR_PRE_RUNTIME_ERROR)
+    }
+
     // Load the .env file if any into the environment variables.
     if let Err(error) = dotenvy::dotenv() {
         io.error(&format!("Failed to load the .env file: {}", error));
         exit!(exit_codes::ERR_ENV_NOT_LOADED)
     }
 
-    // Parse the command line arguments.
-    let cli = cli::Cli::parse();
-
     // build the rocket instance.
     let possible_rocket = rocket_factory::build().await;
 
@@ -43,8 +62,12 @@ async fn main() -> Result<()> {
             bootstrap::launch_server(rocket).await
         },
         // Launch the console.
-        cli::Command::Console { console_command, args } => {
-            bootstrap::launch_console(rocket, console_command, args).await
+        cli::Command::Console { console_command: Some(console_command), args } => {
+            bootstrap::launch_console(rocket, console_command, cli::normalize_args(args)).await
+        }
+        // Launch the interactive console.
+        cli::Command::Console { console_command: None, .. } => {
+            bootstrap::launch_repl(rocket).await
         }
     };
 
@@ -60,13 +83,16 @@ async fn main() -> Result<()> {
                 PreRuntimeErrors::FailedToIgniteRocketInstance(_)=>exit_codes::ERR_ROCKET_IGNITION_FAILED,
                 PreRuntimeErrors::FailedToLaunchRocketInstance(_)=>exit_codes::ERR_ROCKET_LAUNCH_FAILED,
                 PreRuntimeErrors::FailedToGetCommandRegistry=>exit_codes::ERR_COMMAND_REGISTRY_NOT_FOUND,
-                PreRuntimeErrors::FailedToGetCommand(_)=>exit_codes::ERR_COMMAND_NOT_FOUND,
+                PreRuntimeErrors::FailedToGetCommand(_, _)=>exit_codes::ERR_COMMAND_NOT_FOUND,
                 PreRuntimeErrors::FailedToRunCommand(_,_)=>exit_codes::ERR_COMMAND_FAILED,
-                PreRuntimeErrors::CommandSkipped(_) => exit_codes::ERR_COMMAND_SKIPPED, 
+                PreRuntimeErrors::CommandSkipped(_) => exit_codes::ERR_COMMAND_SKIPPED,
+                PreRuntimeErrors::InvalidCommandArguments(_) => exit_codes::ERR_COMMAND_INVALID_ARGS,
+                PreRuntimeErrors::CommandCancelled(_) => exit_codes::ERR_COMMAND_CANCELLED,
+                PreRuntimeErrors::CommandTimedOut(_) => exit_codes::ERR_COMMAND_TIMEOUT,
             };
 
-            // If the error is not a PreRuntimeErrors::FailedToRunCommand enum value, print the error message.
-            if !matches!(downcasted_error, PreRuntimeErrors::FailedToRunCommand(_, _)) {
+            // If the error was not already displayed by the command, print the error message.
+            if !matches!(downcasted_error, PreRuntimeErrors::FailedToRunCommand(_, _) | PreRuntimeErrors::CommandCancelled(_) | PreRuntimeErrors::CommandTimedOut(_)) {
                 io.error(&format!("{}", error));
             }
 
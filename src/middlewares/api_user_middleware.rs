@@ -0,0 +1,167 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use surrealdb::{Surreal, engine::any::Any};
+use thiserror::Error;
+
+use crate::{core::security::{generate_token, hash_secret, verify_secret, DUMMY_SECRET_HASH}, model::api_user::{ApiUser, API_USER_TABLE}};
+
+/// The prefix of all the API keys, to make them easy to recognize.
+pub const API_KEY_TAG: &str = "hms";
+
+/// The length of the public part of an API key.
+const API_KEY_PREFIX_LENGTH: usize = 8;
+
+/// The length of the secret part of an API key.
+const API_KEY_SECRET_LENGTH: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum ApiUserMiddlewareError {
+    #[error("API user {0} already exists.")]
+    AlreadyExists(String),
+    #[error("API user {0} not found.")]
+    NotFound(String),
+    #[error("database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(Clone)]
+/// A middleware that contains all ApiUser related logic.
+pub struct ApiUserMiddleware {
+    /// The database client.
+    pub db: Surreal<Any>,
+}
+
+impl ApiUserMiddleware {
+    /// Create a new ApiUserMiddleware.
+    pub fn new(db: Surreal<Any>) -> Self {
+        Self {
+            db
+        }
+    }
+
+    /// Find all the API users in database, ordered by name.
+    pub async fn find_all(&self) -> Result<Vec<ApiUser>> {
+        let result = self.db
+            .query("SELECT * FROM type::table($table_name) ORDER BY name")
+            .bind(("table_name", API_USER_TABLE))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(ApiUserMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(result?.take(0)?)
+    }
+
+    /// Find an API user by one of its fields.
+    async fn find_one_by(&self, field: &str, value: &str) -> Result<Option<ApiUser>> {
+        let result = self.db
+            .query(format!("SELECT * FROM type::table($table_name) WHERE {} = $value LIMIT 1", field))
+            .bind(("table_name", API_USER_TABLE))
+            .bind(("value", value))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(ApiUserMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        let mut api_users: Vec<ApiUser> = result?.take(0)?;
+
+        Ok(api_users.pop())
+    }
+
+    /// Find an API user by its name.
+    pub async fn find_by_name(&self, name: &str) -> Result<Option<ApiUser>> {
+        self.find_one_by("name", name).await
+    }
+
+    /// Create a new API user in database.
+    ///
+    /// Returns the created API user and its API key, which is not stored and can't be retrieved afterwards.
+    pub async fn create(&self, name: &str, scopes: Vec<String>, expires_at: Option<DateTime<Utc>>) -> Result<(ApiUser, String)> {
+        if self.find_by_name(name).await?.is_some() {
+            bail!(ApiUserMiddlewareError::AlreadyExists(name.to_string()));
+        }
+
+        let key_prefix = generate_token(API_KEY_PREFIX_LENGTH);
+        let api_key = format!("{}_{}_{}", API_KEY_TAG, key_prefix, generate_token(API_KEY_SECRET_LENGTH));
+
+        let created = self.db
+            .create(API_USER_TABLE)
+            .content(ApiUser {
+                id: None,
+                name: name.to_string(),
+                key_prefix,
+                key_hash: hash_secret(&api_key)?,
+                scopes,
+                enabled: true,
+                created_at: Utc::now(),
+                last_used_at: None,
+                expires_at,
+            }).await;
+
+        if let Err(error) = &created {
+            bail!(ApiUserMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok((created?, api_key))
+    }
+
+    /// Update an API user in database.
+    pub async fn update(&self, api_user: &ApiUser) -> Result<ApiUser> {
+        let api_user_id = api_user.id.clone().unwrap();
+
+        let updated = self.db
+            .update((API_USER_TABLE, api_user_id.id))
+            .content(api_user.clone())
+            .await;
+
+        if let Err(error) = &updated {
+            bail!(ApiUserMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(updated?)
+    }
+
+    /// Revoke an API user, its API key will be refused from now on.
+    pub async fn revoke(&self, name: &str) -> Result<ApiUser> {
+        let possible_api_user = self.find_by_name(name).await?;
+
+        if possible_api_user.is_none() {
+            bail!(ApiUserMiddlewareError::NotFound(name.to_string()));
+        }
+
+        let mut api_user = possible_api_user.unwrap();
+        api_user.enabled = false;
+
+        self.update(&api_user).await
+    }
+
+    /// Authenticate an API key.
+    ///
+    /// Returns the matching API user if the key is valid, and the API user is enabled and not expired.
+    pub async fn authenticate(&self, api_key: &str) -> Result<Option<ApiUser>> {
+        let parts = api_key.split('_').collect::<Vec<&str>>();
+
+        let key_prefix = match parts.as_slice() {
+            [API_KEY_TAG, key_prefix, _] => *key_prefix,
+            _ => return Ok(None),
+        };
+
+        let possible_api_user = self.find_one_by("key_prefix", key_prefix).await?;
+
+        // verify against a dummy hash to avoid leaking which prefixes exist.
+        let key_hash = possible_api_user.as_ref().map(|api_user| api_user.key_hash.as_str()).unwrap_or(DUMMY_SECRET_HASH);
+        let is_valid = verify_secret(api_key, key_hash);
+
+        let mut api_user = match possible_api_user {
+            Some(api_user) if is_valid && api_user.enabled && !api_user.is_expired() => api_user,
+            _ => return Ok(None),
+        };
+
+        // keep track of the API user usage.
+        api_user.last_used_at = Some(Utc::now());
+
+        Ok(Some(self.update(&api_user).await?))
+    }
+}
@@ -1,14 +1,36 @@
+use std::cmp::Reverse;
+use std::time::{Duration, Instant};
+
 use anyhow::{Result, bail};
-use chrono::Utc;
-use surrealdb::{Surreal, engine::remote::ws::Client};
+use chrono::{DateTime, Utc};
+use surrealdb::{Surreal, engine::any::Any, sql::{Id, Thing}};
+use serde::Deserialize;
+use serde_json::json;
 use thiserror::Error;
+use tokio::task::JoinHandle;
+
+use crate::{model::{command_log::{CommandLog, CommandProgress, COMMAND_LOG_TABLE, CommandStatus}, command_lock::{CommandLock, COMMAND_LOCK_TABLE}, command_transcript::{CommandTranscript, TranscriptLine, COMMAND_TRANSCRIPT_TABLE}, command_stats::CommandStats}, core::{commands::{command_concurrency::ConcurrencyPolicy, command_context::CommandContext, command_trait::CommandResult}, lease, retention::RetentionPolicy}};
+
+/// The number of command logs deleted by a single query.
+const DELETE_BATCH_SIZE: usize = 500;
 
-use crate::{model::command_log::{CommandLog, COMMAND_LOG_TABLE, CommandStatus}, core::commands::command_trait::CommandResult};
+/// The delay between two attempts to acquire the locks of a queued run.
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The name of a command having command logs.
+#[derive(Deserialize)]
+struct CommandName {
+    command_name: String,
+}
 
 #[derive(Debug, Error)]
 pub enum CommandMiddlewareError {
     #[error("command {0} ({1}) is already running.")]
     AlreadyRunning(String, String),
+    #[error("command {0} can't run now, the lock of {1} is held by another run.")]
+    LockUnavailable(String, String),
+    #[error("command log {0} not found.")]
+    NotFound(String),
     #[error("database error: {0}")]
     DatabaseError(String),
 }
@@ -17,43 +39,194 @@ pub enum CommandMiddlewareError {
 /// A middleware that contains all Command related logic.
 pub struct CommandMiddleware {
     /// The database client.
-    pub db: Surreal<Client>,
+    pub db: Surreal<Any>,
 }
 
 impl CommandMiddleware {
     /// Create a new CommandMiddleware.
-    pub fn new(db: Surreal<Client>) -> Self {
+    pub fn new(db: Surreal<Any>) -> Self {
         Self {
             db
         }
     }
 
-    /// Check if a command is already running in database.
-    pub async fn try_aquire_lock(&self, command_name: &str, command_args: &str) -> Result<()> {
+    /// Acquire the locks of a command run according to its concurrency policy, returning the id reserved for the command log of the run.
+    ///
+    /// When a lock is held by a live run, the locks already claimed are released, then the run is either skipped or retried until the queue timeout of the policy.
+    pub async fn acquire_locks(&self, command_name: &str, command_args: &str, policy: &ConcurrencyPolicy) -> Result<Thing> {
+        let log_id = Thing::from((COMMAND_LOG_TABLE.to_string(), Id::rand()));
+        let started_at = Instant::now();
+
+        loop {
+            let claimed = self.claim_locks(&log_id, command_name, command_args, policy).await;
+
+            let Err(error) = claimed else {
+                return Ok(log_id);
+            };
+
+            self.release_locks_of(&log_id).await?;
+
+            let unavailable = matches!(
+                error.root_cause().downcast_ref::<CommandMiddlewareError>(),
+                Some(CommandMiddlewareError::AlreadyRunning(_, _) | CommandMiddlewareError::LockUnavailable(_, _))
+            );
+
+            let can_wait = policy.queue_timeout.is_some_and(|timeout| started_at.elapsed() + QUEUE_POLL_INTERVAL < timeout);
+
+            if !unavailable || !can_wait {
+                return Err(error);
+            }
+
+            tokio::time::sleep(QUEUE_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Claim all the locks required by a concurrency policy, failing on the first one held by a live run.
+    async fn claim_locks(&self, log_id: &Thing, command_name: &str, command_args: &str, policy: &ConcurrencyPolicy) -> Result<()> {
+        if policy.exclusive_args {
+            let key = vec![command_name.to_string(), command_args.to_string()];
+
+            if !self.claim_lock(key, log_id, command_name, command_args).await? {
+                bail!(CommandMiddlewareError::AlreadyRunning(command_name.to_string(), command_args.to_string()));
+            }
+        }
+
+        for group in policy.groups.iter() {
+            let key = vec!["group".to_string(), group.to_string()];
+
+            if !self.claim_lock(key, log_id, command_name, command_args).await? {
+                bail!(CommandMiddlewareError::LockUnavailable(command_name.to_string(), format!("group {}", group)));
+            }
+        }
+
+        if let Some(max_instances) = policy.max_instances {
+            let mut claimed = false;
+
+            // each instance is a slot, the first free one is claimed.
+            for instance in 1..=max_instances {
+                let key = vec![command_name.to_string(), "instance".to_string(), instance.to_string()];
+
+                if self.claim_lock(key, log_id, command_name, command_args).await? {
+                    claimed = true;
+                    break;
+                }
+            }
+
+            if !claimed {
+                bail!(CommandMiddlewareError::LockUnavailable(command_name.to_string(), format!("max {} instances", max_instances)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Claim a lock, returning false if it is held by a live run.
+    ///
+    /// The lock record id is the key of the lock (e.g. the command name and args), and is claimed in a single conditional update, so only one of concurrent processes can get it. A lock whose lease expired is taken over, marking the log of its previous run as abandoned.
+    async fn claim_lock(&self, key: Vec<String>, log_id: &Thing, command_name: &str, command_args: &str) -> Result<bool> {
+        let now = Utc::now();
+
         let result = self.db
-        .query("SELECT * FROM type::table($table_name) WHERE command_name = $command_name AND command_args = $command_args and status = 'RUNNING'")
-        .bind(("table_name", COMMAND_LOG_TABLE))
-        .bind(("command_name", command_name))
-        .bind(("command_args", command_args))
-        .await;
+            .query("UPDATE type::thing($table_name, $key) SET command_name = $command_name, command_args = $command_args, log = $log, owner = $owner, acquired_at = $now, lease_expires_at = $lease_expires_at WHERE lease_expires_at = NONE OR lease_expires_at <= $now RETURN BEFORE")
+            .bind(("table_name", COMMAND_LOCK_TABLE))
+            .bind(("key", key))
+            .bind(("command_name", command_name))
+            .bind(("command_args", command_args))
+            .bind(("log", log_id.clone()))
+            .bind(("owner", lease::current_owner()))
+            .bind(("now", now))
+            .bind(("lease_expires_at", now + lease::lease_duration()))
+            .await;
 
         if let Err(error) = &result {
             bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
         }
 
-        let exists: Vec<CommandLog> = result?.take(0)?;
+        let claimed: Result<Vec<Option<CommandLock>>, surrealdb::Error> = result?.take(0);
+
+        if let Err(error) = &claimed {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        // nothing is returned when the lock is held by a live run.
+        let Some(previous) = claimed?.into_iter().next() else {
+            return Ok(false);
+        };
+
+        if let Some(previous) = previous {
+            let previous_log: Result<Option<CommandLog>, surrealdb::Error> = self.db.select((COMMAND_LOG_TABLE, previous.log.id.to_raw())).await;
+
+            if let Err(error) = &previous_log {
+                bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+            }
+
+            let previous_log = previous_log?;
 
-        if exists.len() > 0 {
-            bail!(CommandMiddlewareError::AlreadyRunning(command_name.to_string(), command_args.to_string()));
+            // the previous run may have died before creating its log.
+            if let Some(previous_log) = previous_log.filter(|log| log.status == CommandStatus::RUNNING) {
+                self.abandon_log(&previous_log, &format!("lock lease of {} expired, taken over by {}.", previous.owner, lease::current_owner())).await?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Release the locks held by a run, given the id of its command log, e.g. when the log could not be created.
+    pub async fn release_locks_of(&self, log_id: &Thing) -> Result<()> {
+        let result = self.db
+            .query("DELETE type::table($table_name) WHERE log = $log")
+            .bind(("table_name", COMMAND_LOCK_TABLE))
+            .bind(("log", log_id.clone()))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
         }
 
         Ok(())
     }
 
-    /// Create a new command log in database.
-    pub async fn create_log(&self, command_name: &str, command_args: &str) -> Result<CommandLog> {
+    /// Release the locks of a command, if they are still held by the given run.
+    pub async fn release_lock(&self, command_log: &CommandLog) -> Result<()> {
+        self.release_locks_of(command_log.id.as_ref().unwrap()).await
+    }
+
+    /// Find all the command locks, ordered by acquisition date.
+    pub async fn find_locks(&self) -> Result<Vec<CommandLock>> {
+        let result = self.db
+            .query("SELECT * FROM type::table($table_name) ORDER BY acquired_at")
+            .bind(("table_name", COMMAND_LOCK_TABLE))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(result?.take(0)?)
+    }
+
+    /// Find a command log by its record id (without the table name), failing if it does not exist.
+    pub async fn get_by_id(&self, id: &str) -> Result<CommandLog> {
+        let result: Result<Option<CommandLog>, surrealdb::Error> = self.db
+            .select((COMMAND_LOG_TABLE, id))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        match result? {
+            Some(log) => Ok(log),
+            None => bail!(CommandMiddlewareError::NotFound(id.to_string())),
+        }
+    }
+
+    /// Create a new command log in database, with the id reserved by its lock if any.
+    pub async fn create_log(&self, command_name: &str, command_args: &str, log_id: Option<Thing>) -> Result<CommandLog> {
+        let log_id = log_id.unwrap_or_else(|| Thing::from((COMMAND_LOG_TABLE.to_string(), Id::rand())));
+
         let created = self.db
-            .create(COMMAND_LOG_TABLE)
+            .create((COMMAND_LOG_TABLE, log_id.id))
             .content(CommandLog {
                 id: None,
                 command_name: command_name.to_string(),
@@ -63,6 +236,11 @@ impl CommandMiddleware {
                 created_at: Utc::now(),
                 closed_at: None,
                 elapsed: None,
+                owner: Some(lease::current_owner()),
+                heartbeat_at: Some(Utc::now()),
+                progress: None,
+                attempt: 1,
+                previous_attempt: None,
             }).await;
 
         if let Err(error) = &created {
@@ -73,6 +251,8 @@ impl CommandMiddleware {
     }
 
     /// Update a command log in database.
+    ///
+    /// Only the outcome of the run is updated, the fields renewed while it runs (e.g. the progress) are kept.
     pub async fn update_log(&self, command_log: &CommandLog, command_result: CommandResult, message: Option<String>) -> Result<CommandLog> {
         let mut log = command_log.clone();
 
@@ -80,6 +260,8 @@ impl CommandMiddleware {
             CommandResult::SUCCESS => CommandStatus::SUCCESS,
             CommandResult::ERROR => CommandStatus::ERROR,
             CommandResult::SKIPPED => CommandStatus::SKIPPED,
+            CommandResult::TIMEOUT => CommandStatus::TIMEOUT,
+            CommandResult::CANCELLED => CommandStatus::CANCELLED,
         };
 
         log.message = message;
@@ -88,15 +270,549 @@ impl CommandMiddleware {
 
         let log_id = log.id.clone().unwrap();
 
-        let updated = self.db
-            .update((COMMAND_LOG_TABLE, log_id.id))
-            .content(log)
+        // a run abandoned meanwhile, e.g. after its lease expired, keeps its ABANDONED status.
+        let result = self.db
+            .query("UPDATE $log MERGE $outcome WHERE status = 'RUNNING'")
+            .query("SELECT * FROM $log")
+            .bind(("log", log_id))
+            .bind(("outcome", json!({
+                "status": log.status,
+                "message": log.message,
+                "closed_at": log.closed_at,
+                "elapsed": log.elapsed,
+            })))
             .await;
 
+        if let Err(error) = &result {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        let updated: Result<Option<CommandLog>, surrealdb::Error> = result?.take(1);
+
         if let Err(error) = &updated {
             bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
         }
 
-        Ok(updated?)
+        match updated? {
+            Some(updated) => Ok(updated),
+            None => bail!(CommandMiddlewareError::NotFound(command_log.id.as_ref().unwrap().id.to_raw())),
+        }
+    }
+
+    /// Set the attempt number of a command log, linking it to the log of the previous attempt of the run, which it retries.
+    pub async fn link_attempt(&self, command_log: &CommandLog, attempt: u32, previous_attempt: Option<&CommandLog>) -> Result<CommandLog> {
+        let result = self.db
+            .query("UPDATE $log SET attempt = $attempt, previous_attempt = $previous_attempt")
+            .bind(("log", command_log.id.clone().unwrap()))
+            .bind(("attempt", attempt))
+            .bind(("previous_attempt", previous_attempt.and_then(|previous_attempt| previous_attempt.id.clone())))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        let updated: Option<CommandLog> = result?.take(0)?;
+
+        match updated {
+            Some(log) => Ok(log),
+            None => bail!(CommandMiddlewareError::NotFound(command_log.id.as_ref().unwrap().id.to_raw())),
+        }
+    }
+
+    /// Save the progress of a running command.
+    pub async fn update_progress(&self, command_log: &CommandLog, progress: &CommandProgress) -> Result<()> {
+        let result = self.db
+            .query("UPDATE $log SET progress = $progress WHERE status = 'RUNNING'")
+            .bind(("log", command_log.id.clone().unwrap()))
+            .bind(("progress", progress))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Aggregate the finished command logs per command, ordered by command name, optionally only the ones created since a date.
+    ///
+    /// The running logs, without elapsed time yet, are left out.
+    pub async fn find_stats(&self, since: Option<DateTime<Utc>>) -> Result<Vec<CommandStats>> {
+        let result = self.db
+            .query("SELECT command_name, count() AS runs, count(status = 'SUCCESS') AS successes, count(status INSIDE $failures) AS failures, count(status = 'SKIPPED') AS skipped, math::nearestrank(elapsed, 50) AS p50_elapsed, math::nearestrank(elapsed, 95) AS p95_elapsed, math::max(elapsed) AS max_elapsed, math::max(IF status = 'SUCCESS' THEN time::unix(<datetime> created_at) ELSE 0 END) AS last_success_at, math::max(IF status INSIDE $failures THEN time::unix(<datetime> created_at) ELSE 0 END) AS last_failure_at FROM type::table($table_name) WHERE elapsed != NONE AND ($since = NONE OR created_at >= $since) GROUP BY command_name ORDER BY command_name")
+            .bind(("table_name", COMMAND_LOG_TABLE))
+            .bind(("failures", [CommandStatus::ERROR, CommandStatus::TIMEOUT, CommandStatus::ABANDONED]))
+            .bind(("since", since))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        let stats: Result<Vec<CommandStats>, surrealdb::Error> = result?.take(0);
+
+        if let Err(error) = &stats {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(stats?)
+    }
+
+    /// Find the closed command logs to purge according to a retention policy, from the most recent.
+    ///
+    /// The logs older than the max ages are selected by a first query, then the logs beyond the last ones kept of each command by a query per command.
+    pub async fn find_purgeable(&self, policy: &RetentionPolicy, now: DateTime<Utc>) -> Result<Vec<CommandLog>> {
+        let failures = [CommandStatus::ERROR, CommandStatus::TIMEOUT, CommandStatus::ABANDONED];
+        let before = policy.max_age.map(|max_age| now - max_age);
+        let failure_before = policy.failure_max_age.map(|failure_max_age| now - failure_max_age);
+        let mut purgeable = Vec::<CommandLog>::new();
+
+        // failed logs only follow their own max age, when one is given.
+        if before.is_some() || failure_before.is_some() {
+            let result = self.db
+                .query("SELECT * FROM type::table($table_name) WHERE status != 'RUNNING' AND (IF $failure_before != NONE AND status INSIDE $failures THEN created_at < $failure_before ELSE $before != NONE AND created_at < $before END)")
+                .bind(("table_name", COMMAND_LOG_TABLE))
+                .bind(("failures", failures.clone()))
+                .bind(("before", before))
+                .bind(("failure_before", failure_before))
+                .await;
+
+            if let Err(error) = &result {
+                bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+            }
+
+            let logs: Result<Vec<CommandLog>, surrealdb::Error> = result?.take(0);
+
+            if let Err(error) = &logs {
+                bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+            }
+
+            purgeable.extend(logs?);
+        }
+
+        if let Some(keep_last) = policy.keep_last {
+            let result = self.db
+                .query("SELECT command_name FROM type::table($table_name) GROUP BY command_name")
+                .bind(("table_name", COMMAND_LOG_TABLE))
+                .await;
+
+            if let Err(error) = &result {
+                bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+            }
+
+            let commands: Vec<CommandName> = result?.take(0)?;
+
+            for command in commands {
+                let result = self.db
+                    .query("SELECT * FROM type::table($table_name) WHERE command_name = $command_name AND status != 'RUNNING' AND ($failure_before = NONE OR status NOTINSIDE $failures) ORDER BY created_at DESC START $keep_last")
+                    .bind(("table_name", COMMAND_LOG_TABLE))
+                    .bind(("command_name", command.command_name))
+                    .bind(("failures", failures.clone()))
+                    .bind(("failure_before", failure_before))
+                    .bind(("keep_last", keep_last))
+                    .await;
+
+                if let Err(error) = &result {
+                    bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+                }
+
+                let logs: Result<Vec<CommandLog>, surrealdb::Error> = result?.take(0);
+
+                if let Err(error) = &logs {
+                    bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+                }
+
+                purgeable.extend(logs?);
+            }
+        }
+
+        // a log may be selected by both the max age and the last ones kept.
+        purgeable.sort_by_key(|log| (Reverse(log.created_at), log.id.as_ref().map(|id| id.id.to_raw())));
+        purgeable.dedup_by(|a, b| a.id == b.id);
+
+        Ok(purgeable)
+    }
+
+    /// Delete command logs, along with their transcripts, by record id.
+    pub async fn delete_logs(&self, command_logs: &[CommandLog]) -> Result<()> {
+        // the logs are deleted by batches, to keep the queries small.
+        for batch in command_logs.chunks(DELETE_BATCH_SIZE) {
+            let log_ids = batch.iter().filter_map(|log| log.id.clone()).collect::<Vec<Thing>>();
+
+            // the transcript of a log shares its id.
+            let transcript_ids = log_ids
+                .iter()
+                .map(|log_id| Thing::from((COMMAND_TRANSCRIPT_TABLE.to_string(), log_id.id.clone())))
+                .collect::<Vec<Thing>>();
+
+            let result = self.db
+                .query("DELETE $transcript_ids")
+                .query("DELETE $log_ids")
+                .bind(("transcript_ids", transcript_ids))
+                .bind(("log_ids", log_ids))
+                .await;
+
+            if let Err(error) = &result {
+                bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+            }
+
+            if let Err(error) = result?.check() {
+                bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find all the running commands, ordered by creation date.
+    pub async fn find_running(&self) -> Result<Vec<CommandLog>> {
+        let result = self.db
+            .query("SELECT * FROM type::table($table_name) WHERE status = 'RUNNING' ORDER BY created_at")
+            .bind(("table_name", COMMAND_LOG_TABLE))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(result?.take(0)?)
+    }
+
+    /// Renew the lock lease of a running command, returning false if the command is no longer running (e.g. its lock was released or taken over).
+    pub async fn renew_lease(&self, command_log: &CommandLog) -> Result<bool> {
+        let result = self.db
+            .query("UPDATE $log SET heartbeat_at = $now WHERE status = 'RUNNING'")
+            .query("UPDATE type::table($table_name) SET lease_expires_at = $lease_expires_at WHERE log = $log")
+            .bind(("table_name", COMMAND_LOCK_TABLE))
+            .bind(("log", command_log.id.clone().unwrap()))
+            .bind(("now", Utc::now()))
+            .bind(("lease_expires_at", Utc::now() + lease::lease_duration()))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        let renewed: Vec<CommandLog> = result?.take(0)?;
+
+        Ok(!renewed.is_empty())
+    }
+
+    /// Renew the lock lease of a running command in background, until the returned task is aborted.
+    ///
+    /// The run is cancelled when its log is no longer running, e.g. abandoned by another run which took its lock over.
+    pub fn spawn_heartbeat(&self, command_log: &CommandLog, ctx: &CommandContext) -> JoinHandle<()> {
+        let middleware = self.clone();
+        let command_log = command_log.clone();
+        let ctx = ctx.clone();
+
+        tokio::spawn(async move {
+            let interval = lease::heartbeat_interval().to_std().unwrap_or_default();
+
+            loop {
+                tokio::time::sleep(interval).await;
+
+                // a failed renewal is retried on the next beat, before the lease expires.
+                if let Ok(false) = middleware.renew_lease(&command_log).await {
+                    ctx.cancel();
+                    break;
+                }
+            }
+        })
+    }
+
+    /// Save the transcript of a command run, with the same record id as its log.
+    pub async fn save_transcript(&self, command_log: &CommandLog, lines: Vec<TranscriptLine>) -> Result<CommandTranscript> {
+        let log_id = command_log.id.clone().unwrap();
+
+        let created = self.db
+            .create((COMMAND_TRANSCRIPT_TABLE, log_id.id.clone()))
+            .content(CommandTranscript {
+                id: None,
+                log: log_id,
+                lines,
+            }).await;
+
+        if let Err(error) = &created {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(created?)
+    }
+
+    /// Find the transcript of a command run, if it was saved.
+    pub async fn find_transcript(&self, command_log: &CommandLog) -> Result<Option<CommandTranscript>> {
+        let log_id = command_log.id.clone().unwrap();
+
+        let result = self.db
+            .select((COMMAND_TRANSCRIPT_TABLE, log_id.id))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(result?)
+    }
+
+    /// Mark a running command as abandoned, returning none if it was no longer running, e.g. it ended meanwhile.
+    ///
+    /// Only the outcome of the run is updated, the fields renewed while it ran (e.g. the progress) are kept.
+    pub async fn abandon_log(&self, command_log: &CommandLog, message: &str) -> Result<Option<CommandLog>> {
+        let closed_at = Utc::now();
+
+        let result = self.db
+            .query("UPDATE $log MERGE $outcome WHERE status = 'RUNNING'")
+            .bind(("log", command_log.id.clone().unwrap()))
+            .bind(("outcome", json!({
+                "status": CommandStatus::ABANDONED,
+                "message": message,
+                "closed_at": closed_at,
+                "elapsed": closed_at.signed_duration_since(command_log.created_at).num_milliseconds(),
+            })))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        let abandoned: Result<Vec<CommandLog>, surrealdb::Error> = result?.take(0);
+
+        if let Err(error) = &abandoned {
+            bail!(CommandMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(abandoned?.into_iter().next())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::core::database::DatabaseState;
+
+    /// Connects to an empty in-memory database.
+    async fn middleware() -> CommandMiddleware {
+        CommandMiddleware::new(DatabaseState::connect_in_memory().await.get_new_connection())
+    }
+
+    /// The date of a log, in days since the start of the year.
+    fn day(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, day, 0, 0, 0).unwrap()
+    }
+
+    /// The command names and creation days of logs.
+    fn days(logs: &[CommandLog]) -> Vec<(String, u32)> {
+        logs.iter().map(|log| (log.command_name.clone(), log.created_at.format("%d").to_string().parse().unwrap())).collect()
+    }
+
+    /// Saves a command log, the elapsed time being none for running logs.
+    async fn save_log(middleware: &CommandMiddleware, command_name: &str, status: CommandStatus, elapsed: Option<i64>, day: u32) {
+        middleware.db
+            .query("CREATE type::table($table_name) SET command_name = $command_name, command_args = '', status = $status, elapsed = $elapsed, created_at = $created_at")
+            .bind(("table_name", COMMAND_LOG_TABLE))
+            .bind(("command_name", command_name))
+            .bind(("status", status))
+            .bind(("elapsed", elapsed))
+            .bind(("created_at", self::day(day)))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+    }
+
+    /// Claims the lock of a command for a new log.
+    async fn claim(middleware: &CommandMiddleware, command_name: &str) -> (Thing, bool) {
+        let log_id = Thing::from((COMMAND_LOG_TABLE.to_string(), Id::rand()));
+        let claimed = middleware.claim_lock(vec![command_name.to_string()], &log_id, command_name, "").await.unwrap();
+
+        (log_id, claimed)
+    }
+
+    #[tokio::test]
+    async fn a_live_lock_is_refused() {
+        let middleware = middleware().await;
+        let (log_id, claimed) = claim(&middleware, "test:live").await;
+        let log = middleware.create_log("test:live", "", Some(log_id)).await.unwrap();
+        assert!(claimed);
+
+        let (_, claimed) = claim(&middleware, "test:live").await;
+
+        assert!(!claimed);
+        assert_eq!(middleware.get_by_id(&log.id.unwrap().id.to_raw()).await.unwrap().status, CommandStatus::RUNNING);
+    }
+
+    #[tokio::test]
+    async fn an_expired_lock_is_taken_over_abandoning_its_run() {
+        let middleware = middleware().await;
+        let (log_id, _) = claim(&middleware, "test:expired").await;
+        let log = middleware.create_log("test:expired", "", Some(log_id)).await.unwrap();
+
+        // the owner of the lock died, and stopped renewing its lease.
+        middleware.db
+            .query("UPDATE type::table($table_name) SET lease_expires_at = $expired_at")
+            .bind(("table_name", COMMAND_LOCK_TABLE))
+            .bind(("expired_at", Utc::now() - chrono::Duration::seconds(1)))
+            .await
+            .unwrap()
+            .check()
+            .unwrap();
+
+        let (new_log_id, claimed) = claim(&middleware, "test:expired").await;
+
+        assert!(claimed);
+        assert_eq!(middleware.find_locks().await.unwrap()[0].log, new_log_id);
+
+        let previous = middleware.get_by_id(&log.id.unwrap().id.to_raw()).await.unwrap();
+
+        assert_eq!(previous.status, CommandStatus::ABANDONED);
+        assert!(previous.message.unwrap().starts_with("lock lease of"));
+        assert!(previous.elapsed.is_some());
+    }
+
+    #[tokio::test]
+    async fn abandoning_an_ended_log_keeps_its_outcome() {
+        let middleware = middleware().await;
+        let log = middleware.create_log("test:ended", "", None).await.unwrap();
+        middleware.update_log(&log, CommandResult::SUCCESS, None).await.unwrap();
+
+        assert!(middleware.abandon_log(&log, "lock released manually.").await.unwrap().is_none());
+        assert_eq!(middleware.get_by_id(&log.id.unwrap().id.to_raw()).await.unwrap().status, CommandStatus::SUCCESS);
+    }
+
+    #[tokio::test]
+    async fn closing_an_abandoned_log_keeps_it_abandoned() {
+        let middleware = middleware().await;
+        let log = middleware.create_log("test:abandoned", "", None).await.unwrap();
+        middleware.abandon_log(&log, "lock lease expired.").await.unwrap();
+
+        // the abandoned run lost its lease, and its late outcome is ignored.
+        assert!(!middleware.renew_lease(&log).await.unwrap());
+
+        let closed = middleware.update_log(&log, CommandResult::SUCCESS, None).await.unwrap();
+
+        assert_eq!(closed.status, CommandStatus::ABANDONED);
+        assert_eq!(closed.message.as_deref(), Some("lock lease expired."));
+    }
+
+    #[tokio::test]
+    async fn stats_of_no_logs_are_empty() {
+        let middleware = middleware().await;
+
+        assert!(middleware.find_stats(None).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn stats_of_a_single_sample() {
+        let middleware = middleware().await;
+        save_log(&middleware, "test:single", CommandStatus::SUCCESS, Some(42), 1).await;
+
+        let stats = middleware.find_stats(None).await.unwrap();
+
+        assert_eq!(stats.len(), 1);
+        assert_eq!((stats[0].runs, stats[0].successes, stats[0].failures, stats[0].skipped), (1, 1, 0, 0));
+        assert_eq!((stats[0].p50_elapsed, stats[0].p95_elapsed, stats[0].max_elapsed), (42, 42, 42));
+        assert_eq!(stats[0].last_success_at, Some(day(1)));
+        assert_eq!(stats[0].last_failure_at, None);
+    }
+
+    #[tokio::test]
+    async fn stats_use_nearest_rank_percentiles() {
+        let middleware = middleware().await;
+
+        for elapsed in 1..=10 {
+            save_log(&middleware, "test:ranks", CommandStatus::SUCCESS, Some(elapsed * 100), elapsed as u32).await;
+        }
+
+        let stats = middleware.find_stats(None).await.unwrap();
+
+        assert_eq!((stats[0].p50_elapsed, stats[0].p95_elapsed, stats[0].max_elapsed), (500, 1000, 1000));
+    }
+
+    #[tokio::test]
+    async fn stats_leave_out_runs_without_elapsed_time() {
+        let middleware = middleware().await;
+        save_log(&middleware, "test:mixed", CommandStatus::SUCCESS, Some(100), 1).await;
+        save_log(&middleware, "test:mixed", CommandStatus::ERROR, Some(300), 2).await;
+        save_log(&middleware, "test:mixed", CommandStatus::SKIPPED, Some(1), 3).await;
+        save_log(&middleware, "test:mixed", CommandStatus::RUNNING, None, 4).await;
+        save_log(&middleware, "test:running", CommandStatus::RUNNING, None, 4).await;
+
+        let stats = middleware.find_stats(None).await.unwrap();
+
+        assert_eq!(stats.len(), 1);
+        assert_eq!((stats[0].runs, stats[0].successes, stats[0].failures, stats[0].skipped), (3, 1, 1, 1));
+        assert_eq!(stats[0].max_elapsed, 300);
+        assert_eq!(stats[0].last_failure_at, Some(day(2)));
+
+        // only the runs of the time window are aggregated.
+        let stats = middleware.find_stats(Some(day(2))).await.unwrap();
+
+        assert_eq!((stats[0].runs, stats[0].successes), (2, 0));
+        assert_eq!(stats[0].last_success_at, None);
+    }
+
+    #[tokio::test]
+    async fn purges_the_logs_older_than_the_max_age() {
+        let middleware = middleware().await;
+
+        for created_on in 1..=4 {
+            save_log(&middleware, "test:age", CommandStatus::SUCCESS, Some(1), created_on).await;
+        }
+
+        let policy = RetentionPolicy { max_age: Some(chrono::Duration::days(2)), ..Default::default() };
+        let purged = middleware.find_purgeable(&policy, day(5)).await.unwrap();
+
+        assert_eq!(days(&purged), vec![("test:age".to_string(), 2), ("test:age".to_string(), 1)]);
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn keeps_the_last_logs_of_each_command() {
+        let middleware = middleware().await;
+
+        for created_on in 1..=3 {
+            save_log(&middleware, "test:first", CommandStatus::SUCCESS, Some(1), created_on).await;
+            save_log(&middleware, "test:second", CommandStatus::SKIPPED, Some(1), created_on + 10).await;
+        }
+
+        let policy = RetentionPolicy { keep_last: Some(2), ..Default::default() };
+        let purged = middleware.find_purgeable(&policy, day(20)).await.unwrap();
+
+        assert_eq!(days(&purged), vec![("test:second".to_string(), 11), ("test:first".to_string(), 1)]);
+    }
+
+    #[tokio::test]
+    async fn never_purges_the_running_logs() {
+        let middleware = middleware().await;
+        save_log(&middleware, "test:running", CommandStatus::RUNNING, None, 1).await;
+        save_log(&middleware, "test:running", CommandStatus::RUNNING, None, 2).await;
+        save_log(&middleware, "test:running", CommandStatus::SUCCESS, Some(1), 20).await;
+
+        let policy = RetentionPolicy { keep_last: Some(1), max_age: Some(chrono::Duration::days(1)), failure_max_age: None };
+        let purged = middleware.find_purgeable(&policy, day(20)).await.unwrap();
+
+        assert!(purged.is_empty());
+    }
+
+    #[tokio::test]
+    async fn keeps_the_failed_logs_for_their_own_max_age() {
+        let middleware = middleware().await;
+        save_log(&middleware, "test:failed", CommandStatus::ERROR, Some(1), 1).await;
+        save_log(&middleware, "test:failed", CommandStatus::TIMEOUT, Some(1), 8).await;
+        save_log(&middleware, "test:failed", CommandStatus::SUCCESS, Some(1), 9).await;
+        save_log(&middleware, "test:failed", CommandStatus::SUCCESS, Some(1), 10).await;
+
+        let policy = RetentionPolicy { keep_last: Some(1), max_age: Some(chrono::Duration::days(5)), failure_max_age: Some(chrono::Duration::days(5)) };
+        let purged = middleware.find_purgeable(&policy, day(10)).await.unwrap();
+
+        assert_eq!(days(&purged), vec![("test:failed".to_string(), 9), ("test:failed".to_string(), 1)]);
+
+        // the purged logs are deleted by record id.
+        middleware.delete_logs(&purged).await.unwrap();
+        assert_eq!(middleware.find_purgeable(&RetentionPolicy { keep_last: Some(10), ..Default::default() }, day(10)).await.unwrap().len(), 0);
+        assert_eq!(middleware.find_stats(None).await.unwrap()[0].runs, 2);
+    }
+}
@@ -1 +1,6 @@
-pub mod command_middleware;
\ No newline at end of file
+pub mod command_middleware;
+pub mod site_middleware;
+pub mod api_user_middleware;
+pub mod user_middleware;
+pub mod site_access_middleware;
+pub mod schedule_middleware;
\ No newline at end of file
@@ -0,0 +1,135 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use surrealdb::{Surreal, engine::any::Any};
+use thiserror::Error;
+
+use crate::model::schedule::{Schedule, SCHEDULE_TABLE};
+
+#[derive(Debug, Error)]
+pub enum ScheduleMiddlewareError {
+    #[error("schedule {0} not found.")]
+    NotFound(String),
+    #[error("database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(Clone)]
+/// A middleware that contains all Schedule related logic.
+pub struct ScheduleMiddleware {
+    /// The database client.
+    pub db: Surreal<Any>,
+}
+
+impl ScheduleMiddleware {
+    /// Create a new ScheduleMiddleware.
+    pub fn new(db: Surreal<Any>) -> Self {
+        Self {
+            db
+        }
+    }
+
+    /// Find all the schedules in database, ordered by creation date.
+    pub async fn find_all(&self) -> Result<Vec<Schedule>> {
+        let result = self.db
+            .query("SELECT * FROM type::table($table_name) ORDER BY created_at")
+            .bind(("table_name", SCHEDULE_TABLE))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(ScheduleMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(result?.take(0)?)
+    }
+
+    /// Find a schedule by its record id (without the table name).
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<Schedule>> {
+        let result = self.db
+            .select((SCHEDULE_TABLE, id))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(ScheduleMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(result?)
+    }
+
+    /// Create a new schedule in database.
+    pub async fn create(&self, command_name: &str, command_args: Vec<String>, cron: &str) -> Result<Schedule> {
+        let created = self.db
+            .create(SCHEDULE_TABLE)
+            .content(Schedule {
+                id: None,
+                command_name: command_name.to_string(),
+                command_args,
+                cron: cron.trim().to_string(),
+                enabled: true,
+                created_at: Utc::now(),
+                last_tick_at: None,
+            }).await;
+
+        if let Err(error) = &created {
+            bail!(ScheduleMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(created?)
+    }
+
+    /// Delete a schedule from database.
+    pub async fn delete(&self, id: &str) -> Result<Schedule> {
+        let possible_schedule = self.find_by_id(id).await?;
+
+        if possible_schedule.is_none() {
+            bail!(ScheduleMiddlewareError::NotFound(id.to_string()));
+        }
+
+        let deleted: Result<Option<Schedule>, surrealdb::Error> = self.db
+            .delete((SCHEDULE_TABLE, id))
+            .await;
+
+        if let Err(error) = &deleted {
+            bail!(ScheduleMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(possible_schedule.unwrap())
+    }
+
+    /// Claim an occurrence of a schedule, returning false if it was already claimed (e.g. by another server).
+    ///
+    /// The claim is a single conditional update, so only one server can win it.
+    pub async fn claim(&self, schedule: &Schedule, tick: DateTime<Utc>) -> Result<bool> {
+        let result = self.db
+            .query("UPDATE $schedule SET last_tick_at = $tick WHERE last_tick_at = NONE OR last_tick_at < $tick")
+            .bind(("schedule", schedule.id.clone().unwrap()))
+            .bind(("tick", tick))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(ScheduleMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        let claimed: Vec<Schedule> = result?.take(0)?;
+
+        Ok(!claimed.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::database::DatabaseState;
+
+    #[tokio::test]
+    async fn an_occurrence_is_claimed_once() {
+        let schedule_middleware = ScheduleMiddleware::new(DatabaseState::connect_in_memory().await.get_new_connection());
+        let schedule = schedule_middleware.create("test:scheduled", Vec::new(), "0 * * * *").await.unwrap();
+        let tick = Utc::now();
+
+        assert!(schedule_middleware.claim(&schedule, tick).await.unwrap());
+        assert!(!schedule_middleware.claim(&schedule, tick).await.unwrap());
+
+        // the next occurrence can be claimed again.
+        assert!(schedule_middleware.claim(&schedule, tick + chrono::Duration::hours(1)).await.unwrap());
+    }
+}
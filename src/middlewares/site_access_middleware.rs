@@ -0,0 +1,135 @@
+use anyhow::{Result, bail};
+use chrono::{DateTime, Utc};
+use surrealdb::{Surreal, engine::any::Any, sql::Thing};
+use thiserror::Error;
+
+use crate::model::{site::Site, site_access::{SiteAccess, SITE_ACCESS_TABLE}, user::User};
+
+#[derive(Debug, Error)]
+pub enum SiteAccessMiddlewareError {
+    #[error("user {0} has no access to site {1}.")]
+    NotFound(String, String),
+    #[error("database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(Clone)]
+/// A middleware that contains all SiteAccess related logic.
+pub struct SiteAccessMiddleware {
+    /// The database client.
+    pub db: Surreal<Any>,
+}
+
+impl SiteAccessMiddleware {
+    /// Create a new SiteAccessMiddleware.
+    pub fn new(db: Surreal<Any>) -> Self {
+        Self {
+            db
+        }
+    }
+
+    /// Find the site accesses, optionally filtered by user and/or site, ordered by grant date.
+    pub async fn find_all(&self, user: Option<&Thing>, site: Option<&Thing>) -> Result<Vec<SiteAccess>> {
+        let result = self.db
+            .query("SELECT * FROM type::table($table_name) WHERE ($user = NONE OR user = $user) AND ($site = NONE OR site = $site) ORDER BY granted_at")
+            .bind(("table_name", SITE_ACCESS_TABLE))
+            .bind(("user", user))
+            .bind(("site", site))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(SiteAccessMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(result?.take(0)?)
+    }
+
+    /// Find the access of a user to a site, expired or not.
+    pub async fn find_one(&self, user: &Thing, site: &Thing) -> Result<Option<SiteAccess>> {
+        let mut site_accesses = self.find_all(Some(user), Some(site)).await?;
+
+        Ok(site_accesses.pop())
+    }
+
+    /// Either a user has a valid (e.g. not expired) access to a site or not.
+    pub async fn has_access(&self, user: &User, site: &Site) -> Result<bool> {
+        let site_access = self.find_one(user.id.as_ref().unwrap(), site.id.as_ref().unwrap()).await?;
+
+        Ok(site_access.map(|site_access| !site_access.is_expired()).unwrap_or(false))
+    }
+
+    /// Grant a user access to a site.
+    ///
+    /// If the user already has an access to the site, it is renewed with the new grantor and expiration date.
+    pub async fn grant(&self, user: &User, site: &Site, granted_by: &str, expires_at: Option<DateTime<Utc>>) -> Result<SiteAccess> {
+        let user_id = user.id.clone().unwrap();
+        let site_id = site.id.clone().unwrap();
+
+        let existing = self.find_one(&user_id, &site_id).await?;
+
+        let site_access = SiteAccess {
+            id: existing.as_ref().and_then(|site_access| site_access.id.clone()),
+            user: user_id,
+            site: site_id,
+            granted_by: granted_by.to_string(),
+            granted_at: Utc::now(),
+            expires_at,
+        };
+
+        let saved = match &site_access.id {
+            Some(site_access_id) => self.db
+                .update((SITE_ACCESS_TABLE, site_access_id.id.clone()))
+                .content(site_access)
+                .await,
+            None => self.db
+                .create(SITE_ACCESS_TABLE)
+                .content(site_access)
+                .await,
+        };
+
+        if let Err(error) = &saved {
+            bail!(SiteAccessMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(saved?)
+    }
+
+    /// Revoke the access of a user to a site.
+    pub async fn revoke(&self, user: &User, site: &Site) -> Result<SiteAccess> {
+        let possible_site_access = self.find_one(user.id.as_ref().unwrap(), site.id.as_ref().unwrap()).await?;
+
+        if possible_site_access.is_none() {
+            bail!(SiteAccessMiddlewareError::NotFound(user.login.clone(), site.subdomain.clone()));
+        }
+
+        let site_access = possible_site_access.unwrap();
+        let site_access_id = site_access.id.clone().unwrap();
+
+        let deleted: Result<Option<SiteAccess>, surrealdb::Error> = self.db
+            .delete((SITE_ACCESS_TABLE, site_access_id.id))
+            .await;
+
+        if let Err(error) = &deleted {
+            bail!(SiteAccessMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(site_access)
+    }
+
+    /// Revoke all the accesses of a user, e.g. before deleting it.
+    pub async fn revoke_all(&self, user: &User) -> Result<()> {
+        let result = self.db
+            .query("DELETE type::table($table_name) WHERE user = $user")
+            .bind(("table_name", SITE_ACCESS_TABLE))
+            .bind(("user", user.id.clone().unwrap()))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(SiteAccessMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        result?.check()?;
+
+        Ok(())
+    }
+}
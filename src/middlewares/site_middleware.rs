@@ -0,0 +1,234 @@
+use anyhow::{Result, bail};
+use chrono::Utc;
+use surrealdb::{Surreal, engine::any::Any};
+use thiserror::Error;
+
+use crate::model::site::{Site, SiteVisibility, SITE_TABLE};
+
+#[derive(Debug, Error)]
+pub enum SiteMiddlewareError {
+    #[error("site {0} not found.")]
+    NotFound(String),
+    #[error("database error: {0}")]
+    DatabaseError(String),
+}
+
+/// The change applied to a site during a refresh.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SiteChange {
+    /// The site directory appeared, and the site was created.
+    CREATED,
+    /// The site directory re-appeared after being missing.
+    RESTORED,
+    /// The site directory vanished from the sites root.
+    MISSING,
+    /// Nothing changed.
+    UNCHANGED,
+}
+
+/// The result of a refresh of the sites from the sites root.
+#[derive(Debug, Clone, Default)]
+pub struct SiteRefreshReport {
+    /// The sites with their applied change, ordered by subdomain.
+    pub changes: Vec<(Site, SiteChange)>,
+    /// The directories skipped during the refresh, with the reason why.
+    pub warnings: Vec<String>,
+}
+
+impl SiteRefreshReport {
+    /// Returns the number of sites with a given change.
+    pub fn count(&self, change: SiteChange) -> usize {
+        self.changes.iter().filter(|(_, site_change)| *site_change == change).count()
+    }
+}
+
+#[derive(Clone)]
+/// A middleware that contains all Site related logic.
+pub struct SiteMiddleware {
+    /// The database client.
+    pub db: Surreal<Any>,
+}
+
+impl SiteMiddleware {
+    /// Create a new SiteMiddleware.
+    pub fn new(db: Surreal<Any>) -> Self {
+        Self {
+            db
+        }
+    }
+
+    /// Find all the sites in database, ordered by subdomain.
+    pub async fn find_all(&self) -> Result<Vec<Site>> {
+        let result = self.db
+            .query("SELECT * FROM type::table($table_name) ORDER BY subdomain")
+            .bind(("table_name", SITE_TABLE))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(SiteMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(result?.take(0)?)
+    }
+
+    /// Find a site by its subdomain.
+    pub async fn find_by_subdomain(&self, subdomain: &str) -> Result<Option<Site>> {
+        let result = self.db
+            .query("SELECT * FROM type::table($table_name) WHERE subdomain = $subdomain LIMIT 1")
+            .bind(("table_name", SITE_TABLE))
+            .bind(("subdomain", subdomain))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(SiteMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        let mut sites: Vec<Site> = result?.take(0)?;
+
+        Ok(sites.pop())
+    }
+
+    /// Find a site by its subdomain, failing if it does not exist.
+    pub async fn get_by_subdomain(&self, subdomain: &str) -> Result<Site> {
+        let possible_site = self.find_by_subdomain(subdomain).await?;
+
+        if possible_site.is_none() {
+            bail!(SiteMiddlewareError::NotFound(subdomain.to_string()));
+        }
+
+        Ok(possible_site.unwrap())
+    }
+
+    /// Create a new site in database.
+    pub async fn create(&self, directory: &str) -> Result<Site> {
+        let now = Utc::now();
+
+        let created = self.db
+            .create(SITE_TABLE)
+            .content(Site {
+                id: None,
+                name: directory.to_string(),
+                subdomain: directory.to_ascii_lowercase(),
+                path: directory.to_string(),
+                enabled: true,
+                missing: false,
+                visibility: SiteVisibility::default(),
+                created_at: now,
+                updated_at: now,
+            }).await;
+
+        if let Err(error) = &created {
+            bail!(SiteMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(created?)
+    }
+
+    /// Update a site in database.
+    pub async fn update(&self, site: &Site) -> Result<Site> {
+        let mut site = site.clone();
+        site.updated_at = Utc::now();
+
+        let site_id = site.id.clone().unwrap();
+
+        let updated = self.db
+            .update((SITE_TABLE, site_id.id))
+            .content(site)
+            .await;
+
+        if let Err(error) = &updated {
+            bail!(SiteMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(updated?)
+    }
+
+    /// Change the visibility of a site.
+    pub async fn set_visibility(&self, subdomain: &str, visibility: SiteVisibility) -> Result<Site> {
+        let mut site = self.get_by_subdomain(subdomain).await?;
+        site.visibility = visibility;
+
+        self.update(&site).await
+    }
+
+    /// Synchronize the sites in database with the directories of the sites root.
+    ///
+    /// - new directories are created as enabled sites,
+    /// - sites whose directory vanished are marked as missing,
+    /// - missing sites whose directory re-appeared are restored.
+    pub async fn refresh(&self, directories: &[String]) -> Result<SiteRefreshReport> {
+        let mut report = SiteRefreshReport::default();
+        let sites = self.find_all().await?;
+
+        // existing sites, either still present or vanished.
+        for site in sites.iter() {
+            let is_present = directories.contains(&site.path);
+
+            let change = match (is_present, site.missing) {
+                (true, true) => SiteChange::RESTORED,
+                (false, false) => SiteChange::MISSING,
+                _ => SiteChange::UNCHANGED,
+            };
+
+            let site = if change == SiteChange::UNCHANGED {
+                site.clone()
+            } else {
+                let mut site = site.clone();
+                site.missing = !is_present;
+                self.update(&site).await?
+            };
+
+            report.changes.push((site, change));
+        }
+
+        // new directories, skipped when their subdomain is already taken (e.g. `Docs` and `docs`).
+        let mut subdomains = sites.iter().map(|site| site.subdomain.clone()).collect::<Vec<String>>();
+
+        for directory in directories.iter() {
+            if sites.iter().any(|site| &site.path == directory) {
+                continue;
+            }
+
+            let subdomain = directory.to_ascii_lowercase();
+
+            if subdomains.contains(&subdomain) {
+                report.warnings.push(format!("Directory {} skipped, its subdomain {} is already taken.", directory, subdomain));
+                continue;
+            }
+
+            subdomains.push(subdomain);
+
+            let site = self.create(directory).await?;
+            report.changes.push((site, SiteChange::CREATED));
+        }
+
+        report.changes.sort_by(|(a, _), (b, _)| a.subdomain.cmp(&b.subdomain));
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::database::DatabaseState;
+
+    #[tokio::test]
+    async fn skips_the_directories_whose_subdomain_is_taken() {
+        let db = DatabaseState::connect_in_memory().await;
+        let middleware = SiteMiddleware::new(db.get_new_connection());
+
+        let report = middleware.refresh(&["docs".to_string(), "Docs".to_string(), "Blog".to_string()]).await.unwrap();
+
+        assert_eq!(report.count(SiteChange::CREATED), 2);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("Docs"));
+
+        // the skipped directory stays skipped on the next refresh.
+        let report = middleware.refresh(&["docs".to_string(), "Docs".to_string(), "Blog".to_string()]).await.unwrap();
+
+        assert_eq!(report.count(SiteChange::UNCHANGED), 2);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(middleware.find_all().await.unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,207 @@
+use anyhow::{Result, bail};
+use chrono::Utc;
+use surrealdb::{Surreal, engine::any::Any};
+use thiserror::Error;
+
+use crate::{core::security::{hash_secret, verify_secret, DUMMY_SECRET_HASH}, model::user::{User, USER_TABLE}};
+
+/// The minimal length of a user password.
+pub const PASSWORD_MIN_LENGTH: usize = 8;
+
+#[derive(Debug, Error)]
+pub enum UserMiddlewareError {
+    #[error("user {0} already exists.")]
+    AlreadyExists(String),
+    #[error("user {0} not found.")]
+    NotFound(String),
+    #[error("invalid login: {0}")]
+    InvalidLogin(String),
+    #[error("password must be at least {} characters long.", PASSWORD_MIN_LENGTH)]
+    PasswordTooShort,
+    #[error("database error: {0}")]
+    DatabaseError(String),
+}
+
+#[derive(Clone)]
+/// A middleware that contains all User related logic.
+pub struct UserMiddleware {
+    /// The database client.
+    pub db: Surreal<Any>,
+}
+
+impl UserMiddleware {
+    /// Create a new UserMiddleware.
+    pub fn new(db: Surreal<Any>) -> Self {
+        Self {
+            db
+        }
+    }
+
+    /// Normalizes a login, logins are case-insensitive.
+    pub fn normalize_login(login: &str) -> String {
+        login.trim().to_lowercase()
+    }
+
+    /// Find all the users in database, ordered by login.
+    pub async fn find_all(&self) -> Result<Vec<User>> {
+        let result = self.db
+            .query("SELECT * FROM type::table($table_name) ORDER BY login")
+            .bind(("table_name", USER_TABLE))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(UserMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(result?.take(0)?)
+    }
+
+    /// Find a user by its login.
+    pub async fn find_by_login(&self, login: &str) -> Result<Option<User>> {
+        let result = self.db
+            .query("SELECT * FROM type::table($table_name) WHERE login = $login LIMIT 1")
+            .bind(("table_name", USER_TABLE))
+            .bind(("login", Self::normalize_login(login)))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(UserMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        let mut users: Vec<User> = result?.take(0)?;
+
+        Ok(users.pop())
+    }
+
+    /// Find a user by its record id (without the table name).
+    pub async fn find_by_id(&self, id: &str) -> Result<Option<User>> {
+        let result = self.db
+            .select((USER_TABLE, id))
+            .await;
+
+        if let Err(error) = &result {
+            bail!(UserMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(result?)
+    }
+
+    /// Find a user by its login, failing if it does not exist.
+    pub async fn get_by_login(&self, login: &str) -> Result<User> {
+        let possible_user = self.find_by_login(login).await?;
+
+        if possible_user.is_none() {
+            bail!(UserMiddlewareError::NotFound(login.to_string()));
+        }
+
+        Ok(possible_user.unwrap())
+    }
+
+    /// Create a new user in database.
+    pub async fn create(&self, login: &str, password: &str, display_name: &str) -> Result<User> {
+        let login = Self::normalize_login(login);
+
+        if login.is_empty() || login.chars().any(char::is_whitespace) {
+            bail!(UserMiddlewareError::InvalidLogin(login));
+        }
+
+        if self.find_by_login(&login).await?.is_some() {
+            bail!(UserMiddlewareError::AlreadyExists(login));
+        }
+
+        let created = self.db
+            .create(USER_TABLE)
+            .content(User {
+                id: None,
+                display_name: if display_name.trim().is_empty() { login.clone() } else { display_name.trim().to_string() },
+                login,
+                password_hash: Self::hash_password(password)?,
+                enabled: true,
+                created_at: Utc::now(),
+                last_login_at: None,
+            }).await;
+
+        if let Err(error) = &created {
+            bail!(UserMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(created?)
+    }
+
+    /// Update a user in database.
+    pub async fn update(&self, user: &User) -> Result<User> {
+        let user_id = user.id.clone().unwrap();
+
+        let updated = self.db
+            .update((USER_TABLE, user_id.id))
+            .content(user.clone())
+            .await;
+
+        if let Err(error) = &updated {
+            bail!(UserMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(updated?)
+    }
+
+    /// Change the password of a user.
+    pub async fn set_password(&self, login: &str, password: &str) -> Result<User> {
+        let mut user = self.get_by_login(login).await?;
+        user.password_hash = Self::hash_password(password)?;
+
+        self.update(&user).await
+    }
+
+    /// Enable or disable a user.
+    pub async fn set_enabled(&self, login: &str, enabled: bool) -> Result<User> {
+        let mut user = self.get_by_login(login).await?;
+        user.enabled = enabled;
+
+        self.update(&user).await
+    }
+
+    /// Delete a user from database.
+    pub async fn delete(&self, login: &str) -> Result<User> {
+        let user = self.get_by_login(login).await?;
+        let user_id = user.id.clone().unwrap();
+
+        let deleted: Result<Option<User>, surrealdb::Error> = self.db
+            .delete((USER_TABLE, user_id.id))
+            .await;
+
+        if let Err(error) = &deleted {
+            bail!(UserMiddlewareError::DatabaseError(error.to_string()));
+        }
+
+        Ok(user)
+    }
+
+    /// Authenticate a user with its login and password.
+    ///
+    /// Returns the user if the password is valid and the user is enabled, recording the login date.
+    pub async fn authenticate(&self, login: &str, password: &str) -> Result<Option<User>> {
+        let possible_user = self.find_by_login(login).await?;
+
+        // verify against a dummy hash to avoid leaking which logins exist.
+        let password_hash = possible_user.as_ref().map(|user| user.password_hash.as_str()).unwrap_or(DUMMY_SECRET_HASH);
+        let is_valid = verify_secret(password, password_hash);
+
+        let mut user = match possible_user {
+            Some(user) if is_valid && user.enabled => user,
+            _ => return Ok(None),
+        };
+
+        user.last_login_at = Some(Utc::now());
+
+        Ok(Some(self.update(&user).await?))
+    }
+
+    /// Hashes a password, checking its length.
+    pub fn hash_password(password: &str) -> Result<String> {
+        if password.chars().count() < PASSWORD_MIN_LENGTH {
+            bail!(UserMiddlewareError::PasswordTooShort);
+        }
+
+        hash_secret(password)
+    }
+}
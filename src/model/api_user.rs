@@ -0,0 +1,40 @@
+use serde::{Serialize, Deserialize};
+use surrealdb::sql::Thing;
+use chrono::{DateTime, Utc};
+
+/// The API user table name.
+pub const API_USER_TABLE: &str = "api_user";
+
+/// The scope granting access to all the API endpoints.
+pub const SCOPE_ALL: &str = "*";
+
+/// A consumer of the REST API, authenticated by an API key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ApiUser {
+    pub id: Option<Thing>,
+    /// The unique name of the API user.
+    pub name: String,
+    /// The public prefix of the API key, used to find the API user.
+    pub key_prefix: String,
+    /// The argon2id hash of the whole API key.
+    pub key_hash: String,
+    /// The scopes granted to the API user.
+    pub scopes: Vec<String>,
+    /// Either the API user can authenticate or not.
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ApiUser {
+    /// Either the API user was granted a scope or not.
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|granted| granted == scope || granted == SCOPE_ALL)
+    }
+
+    /// Either the API user is expired or not.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.map(|expires_at| expires_at <= Utc::now()).unwrap_or(false)
+    }
+}
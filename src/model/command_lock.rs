@@ -0,0 +1,47 @@
+use std::fmt::Display;
+
+use serde::{Serialize, Deserialize};
+use surrealdb::sql::Thing;
+use chrono::{DateTime, Utc};
+
+/// The command lock table name.
+pub const COMMAND_LOCK_TABLE: &str = "command_lock";
+
+/// The process running a command, e.g. holding its lock.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LockOwner {
+    pub hostname: String,
+    pub pid: u32,
+    /// The start date of the process, as PIDs are reused.
+    pub started_at: DateTime<Utc>,
+}
+
+impl Display for LockOwner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{} (started at {})", self.hostname, self.pid, self.started_at.to_rfc3339())
+    }
+}
+
+/// The lock of a non parallel command.
+///
+/// Its record id is made of the command name and canonical args, so a single record can exist per command and args.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CommandLock {
+    pub id: Option<Thing>,
+    pub command_name: String,
+    pub command_args: String,
+    /// The command log of the run holding the lock.
+    pub log: Thing,
+    /// The process holding the lock.
+    pub owner: LockOwner,
+    pub acquired_at: DateTime<Utc>,
+    /// The expiration of the lock lease, after which another process can take the lock over.
+    pub lease_expires_at: DateTime<Utc>,
+}
+
+impl CommandLock {
+    /// Either the lock lease expired or not.
+    pub fn is_expired(&self) -> bool {
+        self.lease_expires_at <= Utc::now()
+    }
+}
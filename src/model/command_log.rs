@@ -1,7 +1,11 @@
+use std::fmt::Display;
+
 use serde::{Serialize, Deserialize};
 use surrealdb::sql::Thing;
 use chrono::{DateTime, Utc};
 
+use super::command_lock::LockOwner;
+
 /// The command log table name.
 pub const COMMAND_LOG_TABLE: &str = "command_log";
 
@@ -16,6 +20,43 @@ pub enum CommandStatus {
     ERROR,
     /// The command is skipped.
     SKIPPED,
+    /// The command exceeded its max duration.
+    TIMEOUT,
+    /// The command was cancelled, by SIGINT or SIGTERM.
+    CANCELLED,
+    /// The command stopped renewing its lock lease, probably killed, or its lock was released manually.
+    ABANDONED,
+}
+
+/// The progress of a running command, as reported by its logic.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CommandProgress {
+    pub current: u64,
+    pub max: u64,
+    pub message: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl CommandProgress {
+    /// The completion percentage of the progress.
+    pub fn percent(&self) -> u64 {
+        match self.max {
+            0 => 0,
+            max => self.current.min(max) * 100 / max,
+        }
+    }
+}
+
+impl Display for CommandProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{} ({}%)", self.current, self.max, self.percent())?;
+
+        if let Some(message) = &self.message {
+            write!(f, " {}", message)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -27,5 +68,25 @@ pub struct CommandLog {
     pub message: Option<String>,
     pub created_at: DateTime<Utc>,
     pub closed_at: Option<DateTime<Utc>>,
-    pub elapsed: Option<i64>
+    pub elapsed: Option<i64>,
+    /// The process running the command.
+    #[serde(default)]
+    pub owner: Option<LockOwner>,
+    /// The last heartbeat of the process running the command.
+    #[serde(default)]
+    pub heartbeat_at: Option<DateTime<Utc>>,
+    /// The last progress reported by the command, saved at most every few seconds.
+    #[serde(default)]
+    pub progress: Option<CommandProgress>,
+    /// The attempt number of the run, starting at 1, retries of a failed run having their own log.
+    #[serde(default = "first_attempt")]
+    pub attempt: u32,
+    /// The log of the previous attempt, if the run is a retry.
+    #[serde(default)]
+    pub previous_attempt: Option<Thing>,
+}
+
+/// The attempt number of logs saved before retries.
+fn first_attempt() -> u32 {
+    1
 }
\ No newline at end of file
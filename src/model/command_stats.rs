@@ -0,0 +1,42 @@
+use serde::{Serialize, Deserialize, Deserializer};
+use chrono::{DateTime, TimeZone, Utc};
+
+/// The statistics of the finished runs of a command, aggregated from its command logs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandStats {
+    pub command_name: String,
+    pub runs: u64,
+    pub successes: u64,
+    /// The failed runs (ERROR, TIMEOUT and ABANDONED).
+    pub failures: u64,
+    pub skipped: u64,
+    /// The nearest-rank median of the elapsed time, in milliseconds.
+    pub p50_elapsed: i64,
+    /// The nearest-rank 95th percentile of the elapsed time, in milliseconds.
+    pub p95_elapsed: i64,
+    /// The max elapsed time, in milliseconds.
+    pub max_elapsed: i64,
+    /// The start of the last successful run, aggregated as a unix timestamp (0 if none).
+    #[serde(deserialize_with = "from_unix_timestamp")]
+    pub last_success_at: Option<DateTime<Utc>>,
+    /// The start of the last failed run, aggregated as a unix timestamp (0 if none).
+    #[serde(deserialize_with = "from_unix_timestamp")]
+    pub last_failure_at: Option<DateTime<Utc>>,
+}
+
+impl CommandStats {
+    /// The ratio of a number of runs among all the runs, as a percentage.
+    pub fn percent(&self, count: u64) -> f64 {
+        match self.runs {
+            0 => 0.0,
+            runs => count as f64 * 100.0 / runs as f64,
+        }
+    }
+}
+
+/// Deserializes a unix timestamp, 0 meaning no date.
+fn from_unix_timestamp<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error> {
+    let timestamp = i64::deserialize(deserializer)?;
+
+    Ok(Utc.timestamp_opt(timestamp, 0).single().filter(|_| timestamp > 0))
+}
@@ -0,0 +1,36 @@
+use serde::{Serialize, Deserialize};
+use surrealdb::sql::Thing;
+use chrono::{DateTime, Utc};
+
+/// The command transcript table name.
+pub const COMMAND_TRANSCRIPT_TABLE: &str = "command_transcript";
+
+/// The level of a transcript line, e.g. the ConsoleIO method which printed it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TranscriptLevel {
+    /// Plain output, e.g. titles, tables or listings.
+    OUTPUT,
+    SUCCESS,
+    ERROR,
+    WARNING,
+    NOTE,
+    INFO,
+}
+
+/// A line printed by a command, without ANSI codes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TranscriptLine {
+    pub level: TranscriptLevel,
+    /// The printed text, without the level prefix.
+    pub text: String,
+    pub printed_at: DateTime<Utc>,
+}
+
+/// The output of a command run, stored with the same record id as its command log.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct CommandTranscript {
+    pub id: Option<Thing>,
+    /// The command log of the run.
+    pub log: Thing,
+    pub lines: Vec<TranscriptLine>,
+}
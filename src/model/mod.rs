@@ -1 +1,9 @@
-pub mod command_log;
\ No newline at end of file
+pub mod command_log;
+pub mod command_lock;
+pub mod command_transcript;
+pub mod command_stats;
+pub mod site;
+pub mod api_user;
+pub mod user;
+pub mod site_access;
+pub mod schedule;
\ No newline at end of file
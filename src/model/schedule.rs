@@ -0,0 +1,23 @@
+use serde::{Serialize, Deserialize};
+use surrealdb::sql::Thing;
+use chrono::{DateTime, Utc};
+
+/// The schedule table name.
+pub const SCHEDULE_TABLE: &str = "schedule";
+
+/// A console command run periodically by the server.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Schedule {
+    pub id: Option<Thing>,
+    /// The name of the scheduled command.
+    pub command_name: String,
+    /// The arguments given to the command, as typed in the console.
+    pub command_args: Vec<String>,
+    /// The cron expression of the schedule, evaluated in UTC.
+    pub cron: String,
+    /// Either the schedule is run or not.
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    /// The last run claimed by a server, so replicas never run the same occurrence twice.
+    pub last_tick_at: Option<DateTime<Utc>>,
+}
@@ -0,0 +1,75 @@
+use std::str::FromStr;
+
+use serde::{Serialize, Deserialize};
+use surrealdb::sql::Thing;
+use chrono::{DateTime, Utc};
+
+/// The site table name.
+pub const SITE_TABLE: &str = "site";
+
+/// The visibility of a site, e.g. who can access it.
+///
+/// Stored and exchanged with the API in lowercase, as typed in the console.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SiteVisibility {
+    /// Anyone can access the site, without logging in.
+    PUBLIC,
+    /// Any logged in user can access the site.
+    AUTHENTICATED,
+    /// Only the users granted access to the site can access it.
+    #[default]
+    RESTRICTED,
+}
+
+impl SiteVisibility {
+    /// All the visibilities, from the most to the least open.
+    pub const ALL: [SiteVisibility; 3] = [SiteVisibility::PUBLIC, SiteVisibility::AUTHENTICATED, SiteVisibility::RESTRICTED];
+
+    /// The name of the visibility, as used by the console commands.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SiteVisibility::PUBLIC => "public",
+            SiteVisibility::AUTHENTICATED => "authenticated",
+            SiteVisibility::RESTRICTED => "restricted",
+        }
+    }
+
+    /// Either users must log in to access the site or not.
+    pub fn requires_login(&self) -> bool {
+        !matches!(self, SiteVisibility::PUBLIC)
+    }
+}
+
+impl FromStr for SiteVisibility {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        SiteVisibility::ALL
+            .into_iter()
+            .find(|visibility| visibility.as_str().eq_ignore_ascii_case(value.trim()))
+            .ok_or(format!("invalid visibility {}, expected one of public, authenticated, restricted", value))
+    }
+}
+
+/// A static site, mapped to a directory of the sites root.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Site {
+    pub id: Option<Thing>,
+    /// The display name of the site.
+    pub name: String,
+    /// The subdomain the site is served on.
+    pub subdomain: String,
+    /// The directory of the site, relative to the sites root.
+    pub path: String,
+    /// Either the site is served or not.
+    pub enabled: bool,
+    /// Either the site directory vanished from the sites root or not.
+    pub missing: bool,
+    /// Who can access the site, sites are restricted unless stated otherwise.
+    #[serde(default)]
+    pub visibility: SiteVisibility,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
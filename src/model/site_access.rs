@@ -0,0 +1,27 @@
+use serde::{Serialize, Deserialize};
+use surrealdb::sql::Thing;
+use chrono::{DateTime, Utc};
+
+/// The site access table name.
+pub const SITE_ACCESS_TABLE: &str = "site_access";
+
+/// A grant allowing a user to access a protected site.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SiteAccess {
+    pub id: Option<Thing>,
+    /// The user granted.
+    pub user: Thing,
+    /// The site the user can access.
+    pub site: Thing,
+    /// Who granted the access (console user, API user...).
+    pub granted_by: String,
+    pub granted_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl SiteAccess {
+    /// Either the access is expired or not.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.map(|expires_at| expires_at <= Utc::now()).unwrap_or(false)
+    }
+}
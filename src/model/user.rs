@@ -0,0 +1,22 @@
+use serde::{Serialize, Deserialize};
+use surrealdb::sql::Thing;
+use chrono::{DateTime, Utc};
+
+/// The user table name.
+pub const USER_TABLE: &str = "user";
+
+/// A person allowed to log in to protected sites.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct User {
+    pub id: Option<Thing>,
+    /// The unique login of the user, usually an email.
+    pub login: String,
+    /// The argon2id hash of the password.
+    pub password_hash: String,
+    /// The name displayed for the user.
+    pub display_name: String,
+    /// Either the user can log in or not.
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_login_at: Option<DateTime<Utc>>,
+}
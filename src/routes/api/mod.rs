@@ -0,0 +1,127 @@
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::{catch, catchers, Catcher, Request};
+use serde::Serialize;
+
+use crate::guards::api_key::{ApiKeyError, AuthenticatedApiUser};
+use crate::middlewares::{
+    api_user_middleware::ApiUserMiddlewareError,
+    site_access_middleware::SiteAccessMiddlewareError,
+    site_middleware::SiteMiddlewareError,
+    user_middleware::UserMiddlewareError,
+};
+
+pub mod ping;
+pub mod users;
+pub mod sites;
+pub mod site_accesses;
+
+/// The JSON body of API errors.
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiError {
+    /// A short machine-readable error code.
+    pub error: String,
+    /// A human-readable message.
+    pub message: String,
+}
+
+/// The error response of the API endpoints.
+pub type ApiErrorResponse = (Status, Json<ApiError>);
+
+/// The result of the API endpoints.
+pub type ApiResult<T> = Result<T, ApiErrorResponse>;
+
+impl ApiError {
+    /// Creates a new ApiError.
+    pub fn new(error: &str, message: &str) -> Self {
+        Self {
+            error: error.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// Creates an error response.
+    pub fn response(status: Status, message: &str) -> ApiErrorResponse {
+        (status, Json(Self::new(&Self::code(status), message)))
+    }
+
+    /// Creates an error response from a middleware error, with the matching HTTP status.
+    pub fn from_error(error: anyhow::Error) -> ApiErrorResponse {
+        let root_cause = error.root_cause();
+
+        let status = if let Some(error) = root_cause.downcast_ref::<UserMiddlewareError>() {
+            match error {
+                UserMiddlewareError::NotFound(_) => Status::NotFound,
+                UserMiddlewareError::AlreadyExists(_) => Status::Conflict,
+                UserMiddlewareError::InvalidLogin(_) | UserMiddlewareError::PasswordTooShort => Status::UnprocessableEntity,
+                UserMiddlewareError::DatabaseError(_) => Status::InternalServerError,
+            }
+        } else if let Some(error) = root_cause.downcast_ref::<SiteMiddlewareError>() {
+            match error {
+                SiteMiddlewareError::NotFound(_) => Status::NotFound,
+                SiteMiddlewareError::DatabaseError(_) => Status::InternalServerError,
+            }
+        } else if let Some(error) = root_cause.downcast_ref::<SiteAccessMiddlewareError>() {
+            match error {
+                SiteAccessMiddlewareError::NotFound(_, _) => Status::NotFound,
+                SiteAccessMiddlewareError::DatabaseError(_) => Status::InternalServerError,
+            }
+        } else if let Some(error) = root_cause.downcast_ref::<ApiUserMiddlewareError>() {
+            match error {
+                ApiUserMiddlewareError::NotFound(_) => Status::NotFound,
+                ApiUserMiddlewareError::AlreadyExists(_) => Status::Conflict,
+                ApiUserMiddlewareError::DatabaseError(_) => Status::InternalServerError,
+            }
+        } else {
+            Status::InternalServerError
+        };
+
+        // do not leak internal errors.
+        if status == Status::InternalServerError {
+            return Self::response(status, "an internal error occurred.");
+        }
+
+        Self::response(status, &error.to_string())
+    }
+
+    /// Returns the error code of a status, e.g. `not_found` for 404.
+    fn code(status: Status) -> String {
+        status
+            .reason()
+            .unwrap_or("error")
+            .to_lowercase()
+            .replace(' ', "_")
+    }
+}
+
+/// Checks that the API user was granted a scope.
+pub fn require_scope(api_user: &AuthenticatedApiUser, scope: &str) -> ApiResult<()> {
+    if !api_user.0.has_scope(scope) {
+        return Err(ApiError::response(Status::Forbidden, &format!("the {} scope is required.", scope)));
+    }
+
+    Ok(())
+}
+
+/// Returns the catchers of the API, to be registered on the API base path.
+pub fn catchers() -> Vec<Catcher> {
+    catchers![unauthorized, default]
+}
+
+/// Renders authentication failures of the API as JSON.
+#[catch(401)]
+fn unauthorized(request: &Request) -> Json<ApiError> {
+    let message = request
+        .local_cache(|| None::<ApiKeyError>)
+        .as_ref()
+        .map(|error| error.to_string())
+        .unwrap_or("authentication required.".to_string());
+
+    Json(ApiError::new("unauthorized", &message))
+}
+
+/// Renders the other errors of the API (unknown routes, invalid payloads...) as JSON.
+#[catch(default)]
+fn default(status: Status, _request: &Request) -> ApiErrorResponse {
+    ApiError::response(status, status.reason().unwrap_or("error"))
+}
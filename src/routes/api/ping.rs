@@ -0,0 +1,13 @@
+use rocket::get;
+use rocket::serde::json::{json, Value};
+
+use crate::guards::api_key::AuthenticatedApiUser;
+
+/// Checks that the API is up and the API key is valid.
+#[get("/ping")]
+pub fn ping(api_user: AuthenticatedApiUser) -> Value {
+    json!({
+        "status": "ok",
+        "api_user": api_user.0.name,
+    })
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::{delete, get, post, State};
+
+use crate::core::database::{Connected, DatabaseState};
+use crate::dto::site_access_dto::{GrantSiteAccessDto, SiteAccessDto};
+use crate::guards::api_key::AuthenticatedApiUser;
+use crate::middlewares::{site_access_middleware::SiteAccessMiddleware, site_middleware::SiteMiddleware, user_middleware::UserMiddleware};
+
+use super::{require_scope, ApiError, ApiResult};
+
+/// The scope required by the site accesses endpoints.
+const SCOPE: &str = "access";
+
+/// Lists the site accesses, optionally filtered by user login and/or site subdomain.
+#[get("/access?<login>&<site>")]
+pub async fn list(api_user: AuthenticatedApiUser, login: Option<&str>, site: Option<&str>, db_conn: &State<DatabaseState<Connected>>) -> ApiResult<Json<Vec<SiteAccessDto>>> {
+    require_scope(&api_user, SCOPE)?;
+
+    let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+    let site_middleware = SiteMiddleware::new(db_conn.get_new_connection());
+    let site_access_middleware = SiteAccessMiddleware::new(db_conn.get_new_connection());
+
+    // resolve the filters.
+    let user = match login {
+        Some(login) => Some(user_middleware.get_by_login(login).await.map_err(ApiError::from_error)?),
+        None => None,
+    };
+
+    let site = match site {
+        Some(subdomain) => Some(site_middleware.get_by_subdomain(subdomain).await.map_err(ApiError::from_error)?),
+        None => None,
+    };
+
+    let site_accesses = site_access_middleware
+        .find_all(
+            user.as_ref().and_then(|user| user.id.as_ref()),
+            site.as_ref().and_then(|site| site.id.as_ref()),
+        )
+        .await
+        .map_err(ApiError::from_error)?;
+
+    // index users and sites to expose their login and subdomain.
+    let logins = user_middleware.find_all().await.map_err(ApiError::from_error)?
+        .into_iter()
+        .map(|user| (user.id.unwrap(), user.login))
+        .collect::<HashMap<_, _>>();
+
+    let subdomains = site_middleware.find_all().await.map_err(ApiError::from_error)?
+        .into_iter()
+        .map(|site| (site.id.unwrap(), site.subdomain))
+        .collect::<HashMap<_, _>>();
+
+    let site_accesses = site_accesses
+        .into_iter()
+        .map(|site_access| {
+            let login = logins.get(&site_access.user).cloned().unwrap_or(site_access.user.to_string());
+            let subdomain = subdomains.get(&site_access.site).cloned().unwrap_or(site_access.site.to_string());
+
+            SiteAccessDto::new(site_access, &login, &subdomain)
+        })
+        .collect();
+
+    Ok(Json(site_accesses))
+}
+
+/// Grants a user access to a site, renewing the existing access if any.
+#[post("/access", data = "<payload>")]
+pub async fn grant(api_user: AuthenticatedApiUser, payload: Json<GrantSiteAccessDto>, db_conn: &State<DatabaseState<Connected>>) -> ApiResult<(Status, Json<SiteAccessDto>)> {
+    require_scope(&api_user, SCOPE)?;
+
+    let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+    let site_middleware = SiteMiddleware::new(db_conn.get_new_connection());
+    let site_access_middleware = SiteAccessMiddleware::new(db_conn.get_new_connection());
+
+    let user = user_middleware.get_by_login(&payload.login).await.map_err(ApiError::from_error)?;
+    let site = site_middleware.get_by_subdomain(&payload.site).await.map_err(ApiError::from_error)?;
+
+    let site_access = site_access_middleware
+        .grant(&user, &site, &format!("api:{}", api_user.0.name), payload.expires_at)
+        .await
+        .map_err(ApiError::from_error)?;
+
+    Ok((Status::Created, Json(SiteAccessDto::new(site_access, &user.login, &site.subdomain))))
+}
+
+/// Revokes the access of a user to a site.
+#[delete("/access/<login>/<site>")]
+pub async fn revoke(api_user: AuthenticatedApiUser, login: &str, site: &str, db_conn: &State<DatabaseState<Connected>>) -> ApiResult<Status> {
+    require_scope(&api_user, SCOPE)?;
+
+    let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+    let site_middleware = SiteMiddleware::new(db_conn.get_new_connection());
+    let site_access_middleware = SiteAccessMiddleware::new(db_conn.get_new_connection());
+
+    let user = user_middleware.get_by_login(login).await.map_err(ApiError::from_error)?;
+    let site = site_middleware.get_by_subdomain(site).await.map_err(ApiError::from_error)?;
+
+    site_access_middleware.revoke(&user, &site).await.map_err(ApiError::from_error)?;
+
+    Ok(Status::NoContent)
+}
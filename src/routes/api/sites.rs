@@ -0,0 +1,59 @@
+use rocket::serde::json::Json;
+use rocket::{get, patch, State};
+
+use crate::core::database::{Connected, DatabaseState};
+use crate::dto::site_dto::{SiteDto, UpdateSiteDto};
+use crate::guards::api_key::AuthenticatedApiUser;
+use crate::middlewares::site_middleware::SiteMiddleware;
+
+use super::{require_scope, ApiError, ApiResult};
+
+/// The scope required by the sites endpoints.
+const SCOPE: &str = "sites";
+
+/// Lists the sites.
+#[get("/sites")]
+pub async fn list(api_user: AuthenticatedApiUser, db_conn: &State<DatabaseState<Connected>>) -> ApiResult<Json<Vec<SiteDto>>> {
+    require_scope(&api_user, SCOPE)?;
+
+    let site_middleware = SiteMiddleware::new(db_conn.get_new_connection());
+    let sites = site_middleware.find_all().await.map_err(ApiError::from_error)?;
+
+    Ok(Json(sites.into_iter().map(SiteDto::from).collect()))
+}
+
+/// Gets a site by its subdomain.
+#[get("/sites/<subdomain>")]
+pub async fn get(api_user: AuthenticatedApiUser, subdomain: &str, db_conn: &State<DatabaseState<Connected>>) -> ApiResult<Json<SiteDto>> {
+    require_scope(&api_user, SCOPE)?;
+
+    let site_middleware = SiteMiddleware::new(db_conn.get_new_connection());
+    let site = site_middleware.get_by_subdomain(subdomain).await.map_err(ApiError::from_error)?;
+
+    Ok(Json(site.into()))
+}
+
+/// Updates a site, only the given fields are changed.
+#[patch("/sites/<subdomain>", data = "<payload>")]
+pub async fn update(api_user: AuthenticatedApiUser, subdomain: &str, payload: Json<UpdateSiteDto>, db_conn: &State<DatabaseState<Connected>>) -> ApiResult<Json<SiteDto>> {
+    require_scope(&api_user, SCOPE)?;
+
+    let site_middleware = SiteMiddleware::new(db_conn.get_new_connection());
+    let mut site = site_middleware.get_by_subdomain(subdomain).await.map_err(ApiError::from_error)?;
+
+    if let Some(name) = &payload.name {
+        site.name = name.trim().to_string();
+    }
+
+    if let Some(enabled) = payload.enabled {
+        site.enabled = enabled;
+    }
+
+    if let Some(visibility) = payload.visibility {
+        site.visibility = visibility;
+    }
+
+    let site = site_middleware.update(&site).await.map_err(ApiError::from_error)?;
+
+    Ok(Json(site.into()))
+}
@@ -0,0 +1,91 @@
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::{delete, get, patch, post, State};
+
+use crate::core::database::{Connected, DatabaseState};
+use crate::dto::user_dto::{CreateUserDto, UpdateUserDto, UserDto};
+use crate::guards::api_key::AuthenticatedApiUser;
+use crate::middlewares::{site_access_middleware::SiteAccessMiddleware, user_middleware::UserMiddleware};
+
+use super::{require_scope, ApiError, ApiResult};
+
+/// The scope required by the users endpoints.
+const SCOPE: &str = "users";
+
+/// Lists the users.
+#[get("/users")]
+pub async fn list(api_user: AuthenticatedApiUser, db_conn: &State<DatabaseState<Connected>>) -> ApiResult<Json<Vec<UserDto>>> {
+    require_scope(&api_user, SCOPE)?;
+
+    let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+    let users = user_middleware.find_all().await.map_err(ApiError::from_error)?;
+
+    Ok(Json(users.into_iter().map(UserDto::from).collect()))
+}
+
+/// Gets a user by its login.
+#[get("/users/<login>")]
+pub async fn get(api_user: AuthenticatedApiUser, login: &str, db_conn: &State<DatabaseState<Connected>>) -> ApiResult<Json<UserDto>> {
+    require_scope(&api_user, SCOPE)?;
+
+    let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+    let user = user_middleware.get_by_login(login).await.map_err(ApiError::from_error)?;
+
+    Ok(Json(user.into()))
+}
+
+/// Creates a user.
+#[post("/users", data = "<payload>")]
+pub async fn create(api_user: AuthenticatedApiUser, payload: Json<CreateUserDto>, db_conn: &State<DatabaseState<Connected>>) -> ApiResult<(Status, Json<UserDto>)> {
+    require_scope(&api_user, SCOPE)?;
+
+    let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+
+    let user = user_middleware
+        .create(&payload.login, &payload.password, &payload.display_name)
+        .await
+        .map_err(ApiError::from_error)?;
+
+    Ok((Status::Created, Json(user.into())))
+}
+
+/// Updates a user, only the given fields are changed.
+#[patch("/users/<login>", data = "<payload>")]
+pub async fn update(api_user: AuthenticatedApiUser, login: &str, payload: Json<UpdateUserDto>, db_conn: &State<DatabaseState<Connected>>) -> ApiResult<Json<UserDto>> {
+    require_scope(&api_user, SCOPE)?;
+
+    let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+    let mut user = user_middleware.get_by_login(login).await.map_err(ApiError::from_error)?;
+
+    if let Some(display_name) = &payload.display_name {
+        user.display_name = display_name.trim().to_string();
+    }
+
+    if let Some(password) = &payload.password {
+        user.password_hash = UserMiddleware::hash_password(password).map_err(ApiError::from_error)?;
+    }
+
+    if let Some(enabled) = payload.enabled {
+        user.enabled = enabled;
+    }
+
+    let user = user_middleware.update(&user).await.map_err(ApiError::from_error)?;
+
+    Ok(Json(user.into()))
+}
+
+/// Deletes a user and its site accesses.
+#[delete("/users/<login>")]
+pub async fn delete(api_user: AuthenticatedApiUser, login: &str, db_conn: &State<DatabaseState<Connected>>) -> ApiResult<Status> {
+    require_scope(&api_user, SCOPE)?;
+
+    let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+    let site_access_middleware = SiteAccessMiddleware::new(db_conn.get_new_connection());
+
+    let user = user_middleware.get_by_login(login).await.map_err(ApiError::from_error)?;
+
+    site_access_middleware.revoke_all(&user).await.map_err(ApiError::from_error)?;
+    user_middleware.delete(&user.login).await.map_err(ApiError::from_error)?;
+
+    Ok(Status::NoContent)
+}
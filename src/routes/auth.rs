@@ -0,0 +1,66 @@
+// the FromForm derive of rocket 0.5.0-rc.3 emits a lint attribute removed from recent compilers.
+#![allow(renamed_and_removed_lints)]
+
+use rocket::form::{Form, FromForm};
+use rocket::http::{CookieJar, Status};
+use rocket::response::{content::RawHtml, Redirect};
+use rocket::{get, post, State};
+
+use crate::core::database::{Connected, DatabaseState};
+use crate::guards::session::Session;
+use crate::guards::site::RequestedSite;
+use crate::middlewares::user_middleware::UserMiddleware;
+
+use super::pages::{login_page, login_url, safe_redirect};
+
+/// The login form payload.
+#[derive(Debug, FromForm)]
+pub struct LoginForm {
+    pub login: String,
+    pub password: String,
+    pub redirect: Option<String>,
+}
+
+/// Displays the login page of a site.
+#[get("/login?<redirect>")]
+pub fn login_form(site: RequestedSite, redirect: Option<String>) -> RawHtml<String> {
+    login_page(&site.0.name, &safe_redirect(redirect.as_deref()), "", None)
+}
+
+/// Authenticates a user on a site, opening a session for the site subdomain.
+///
+/// On failure, the login page is displayed again with an error.
+#[post("/login", data = "<form>")]
+pub async fn login(
+    site: RequestedSite,
+    form: Form<LoginForm>,
+    cookies: &CookieJar<'_>,
+    db_conn: &State<DatabaseState<Connected>>,
+) -> Result<Redirect, (Status, RawHtml<String>)> {
+    let user_middleware = UserMiddleware::new(db_conn.get_new_connection());
+    let redirect = safe_redirect(form.redirect.as_deref());
+
+    let possible_user = user_middleware
+        .authenticate(&form.login, &form.password)
+        .await;
+
+    let error = match possible_user {
+        Ok(Some(user)) => {
+            Session::new(&user, &site.0.subdomain).save(cookies);
+
+            return Ok(Redirect::to(redirect));
+        }
+        Ok(None) => (Status::Unauthorized, "Invalid login or password."),
+        Err(_) => (Status::InternalServerError, "An error occurred, please try again."),
+    };
+
+    Err((error.0, login_page(&site.0.name, &redirect, &form.login, Some(error.1))))
+}
+
+/// Closes the session of the site subdomain.
+#[get("/logout")]
+pub fn logout(_site: RequestedSite, cookies: &CookieJar<'_>) -> Redirect {
+    Session::clear(cookies);
+
+    Redirect::to(login_url("/"))
+}
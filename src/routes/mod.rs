@@ -0,0 +1,4 @@
+pub mod static_site;
+pub mod pages;
+pub mod auth;
+pub mod api;
@@ -0,0 +1,108 @@
+use rocket::http::RawStr;
+use rocket::response::content::RawHtml;
+
+/// The base path of the pages served by Hermes itself on every site.
+pub const RESERVED_PATH: &str = "/__hermes";
+
+/// The layout of the pages served by Hermes.
+const LAYOUT_TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <meta name="robots" content="noindex">
+    <title>{title}</title>
+    <style>
+        body { font-family: system-ui, sans-serif; background: #f4f5f7; color: #1f2328; display: flex; min-height: 100vh; margin: 0; align-items: center; justify-content: center; }
+        main { background: #fff; border-radius: 8px; box-shadow: 0 1px 4px rgba(0, 0, 0, .15); padding: 2rem; width: 100%; max-width: 22rem; }
+        h1 { font-size: 1.25rem; margin-top: 0; }
+        label { display: block; font-size: .9rem; margin: 1rem 0 .25rem; }
+        input { box-sizing: border-box; width: 100%; padding: .5rem; border: 1px solid #c9ced6; border-radius: 4px; }
+        button { margin-top: 1.5rem; width: 100%; padding: .6rem; border: 0; border-radius: 4px; background: #1f6feb; color: #fff; font-size: 1rem; cursor: pointer; }
+        .error { background: #ffebe9; border: 1px solid #ff8182; border-radius: 4px; padding: .5rem; font-size: .9rem; }
+    </style>
+</head>
+<body>
+    <main>
+{content}
+    </main>
+</body>
+</html>
+"#;
+
+/// The login form.
+const LOGIN_TEMPLATE: &str = r#"        <h1>{site_name}</h1>
+        {error}
+        <form method="post" action="{reserved_path}/login">
+            <input type="hidden" name="redirect" value="{redirect}">
+            <label for="login">Login</label>
+            <input id="login" name="login" type="text" autocomplete="username" value="{login}" required autofocus>
+            <label for="password">Password</label>
+            <input id="password" name="password" type="password" autocomplete="current-password" required>
+            <button type="submit">Log in</button>
+        </form>"#;
+
+/// The page displayed to users without access to a site.
+const FORBIDDEN_TEMPLATE: &str = r#"        <h1>{site_name}</h1>
+        <p class="error">You are logged in as {user_name}, but you don't have access to this site.</p>
+        <p><a href="{reserved_path}/logout">Log in as another user</a></p>"#;
+
+/// Escapes a value to be inserted in HTML.
+fn escape(value: &str) -> String {
+    RawStr::new(value).html_escape().to_string()
+}
+
+/// Renders a page within the layout, `content` must already be escaped.
+fn render(title: &str, content: &str) -> RawHtml<String> {
+    RawHtml(
+        LAYOUT_TEMPLATE
+            .replace("{title}", &escape(title))
+            .replace("{content}", content),
+    )
+}
+
+/// Renders the login page of a site.
+pub fn login_page(site_name: &str, redirect: &str, login: &str, error: Option<&str>) -> RawHtml<String> {
+    let error = error
+        .map(|error| format!(r#"<p class="error">{}</p>"#, escape(error)))
+        .unwrap_or_default();
+
+    let content = LOGIN_TEMPLATE
+        .replace("{site_name}", &escape(site_name))
+        .replace("{error}", &error)
+        .replace("{reserved_path}", RESERVED_PATH)
+        .replace("{redirect}", &escape(redirect))
+        .replace("{login}", &escape(login));
+
+    render(&format!("Log in - {}", site_name), &content)
+}
+
+/// Renders the page displayed to a user without access to a site.
+pub fn forbidden_page(site_name: &str, user_name: &str) -> RawHtml<String> {
+    let content = FORBIDDEN_TEMPLATE
+        .replace("{site_name}", &escape(site_name))
+        .replace("{user_name}", &escape(user_name))
+        .replace("{reserved_path}", RESERVED_PATH);
+
+    render(&format!("Access denied - {}", site_name), &content)
+}
+
+/// Returns the URL of the login page, redirecting to `redirect` once logged in.
+pub fn login_url(redirect: &str) -> String {
+    format!("{}/login?redirect={}", RESERVED_PATH, RawStr::new(redirect).percent_encode())
+}
+
+/// Sanitizes a redirection target, only local paths outside of the reserved path are allowed.
+pub fn safe_redirect(redirect: Option<&str>) -> String {
+    match redirect {
+        Some(redirect)
+            if redirect.starts_with('/')
+                && !redirect.starts_with("//")
+                && !redirect.starts_with("/\\")
+                && !redirect.starts_with(RESERVED_PATH) =>
+        {
+            redirect.to_string()
+        }
+        _ => "/".to_string(),
+    }
+}
@@ -0,0 +1,82 @@
+use rocket::fs::NamedFile;
+use rocket::get;
+use rocket::http::uri::{fmt::Path, Origin, Segments};
+use rocket::http::Status;
+use rocket::response::{content::RawHtml, Redirect, Responder};
+use rocket::State;
+
+use crate::core::database::{Connected, DatabaseState};
+use crate::core::sites_root::{SitesRoot, SitesRootError};
+use crate::guards::session::AuthenticatedUser;
+use crate::guards::site::RequestedSite;
+use crate::middlewares::site_access_middleware::SiteAccessMiddleware;
+use crate::model::site::SiteVisibility;
+
+use super::pages::{forbidden_page, login_url};
+
+/// The response of a static site request.
+#[derive(Responder)]
+pub enum SiteResponse {
+    /// The requested file.
+    File(NamedFile),
+    /// A redirection to the login page.
+    Login(Redirect),
+    /// The logged in user has no access to the site.
+    Forbidden((Status, RawHtml<String>)),
+}
+
+/// Serves a file of the static site matching the request subdomain.
+///
+/// This is a catch-all route, ranked last so reserved routes always take precedence.
+#[get("/<path..>", rank = 100)]
+pub async fn serve(
+    site: RequestedSite,
+    user: Option<AuthenticatedUser>,
+    origin: &Origin<'_>,
+    path: Segments<'_, Path>,
+    sites_root: &State<SitesRoot>,
+    db_conn: &State<DatabaseState<Connected>>,
+) -> Result<SiteResponse, Status> {
+    let site = site.0;
+
+    if site.visibility.requires_login() {
+        // anonymous users of non-public sites must log in first.
+        if user.is_none() {
+            return Ok(SiteResponse::Login(Redirect::to(login_url(&origin.to_string()))));
+        }
+    }
+
+    if site.visibility == SiteVisibility::RESTRICTED {
+        // logged in users of restricted sites must have been granted access to the site.
+        let user = user.unwrap().0;
+        let site_access_middleware = SiteAccessMiddleware::new(db_conn.get_new_connection());
+
+        let has_access = site_access_middleware
+            .has_access(&user, &site)
+            .await
+            .map_err(|_| Status::InternalServerError)?;
+
+        if !has_access {
+            return Ok(SiteResponse::Forbidden((Status::Forbidden, forbidden_page(&site.name, &user.display_name))));
+        }
+    }
+
+    let segments = path.collect::<Vec<&str>>();
+
+    // resolve the requested file inside the site directory.
+    let resolved = sites_root.resolve_file(&site.path, &segments).await;
+
+    let file_path = match resolved {
+        Ok(file_path) => file_path,
+        Err(SitesRootError::InvalidPath(_)) => return Err(Status::BadRequest),
+        Err(SitesRootError::Forbidden(_)) => return Err(Status::Forbidden),
+        Err(SitesRootError::NotFound(_)) => return Err(Status::NotFound),
+        Err(SitesRootError::FilesystemError(_)) => return Err(Status::InternalServerError),
+    };
+
+    // content type is guessed from the file extension.
+    NamedFile::open(file_path)
+        .await
+        .map(SiteResponse::File)
+        .map_err(|_| Status::NotFound)
+}